@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc::{q03, read_input::input_from_bytes};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = q03::load_schematic(input_from_bytes(data.to_vec()));
+    let _ = q03::part2(input_from_bytes(data.to_vec()));
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc::{q04, read_input::input_from_bytes};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = q04::load_cards(input_from_bytes(data.to_vec()));
+    let _ = q04::part1(input_from_bytes(data.to_vec()));
+    let _ = q04::part2(input_from_bytes(data.to_vec()));
+});
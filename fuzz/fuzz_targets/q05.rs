@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc::{q05, read_input::input_from_bytes};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = q05::load_seeds(input_from_bytes(data.to_vec()));
+    let _ = q05::part1(input_from_bytes(data.to_vec()));
+    let _ = q05::part2(input_from_bytes(data.to_vec()));
+});
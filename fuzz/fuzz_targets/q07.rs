@@ -0,0 +1,17 @@
+#![no_main]
+
+use aoc::{
+    q07,
+    read_input::{input_from_bytes, input_text},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = input_text(data) {
+        let _ = q07::load_hands::<q07::Card>(text, &q07::Q1Strategy);
+        let _ = q07::part1_text(text);
+        let _ = q07::part2_text(text);
+    }
+    let _ = q07::part1(input_from_bytes(data.to_vec()));
+    let _ = q07::part2(input_from_bytes(data.to_vec()));
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use std::time::Duration;
+
+use aoc::{
+    cancel, q08,
+    read_input::{input_from_bytes, input_text},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The walks are bounded by nodes times directions, which can still be
+    // long, so they run on a budget.
+    let budget = Some(Duration::from_millis(100));
+    if let Ok(text) = input_text(data) {
+        let _ = q08::load_network(text);
+        let _ = cancel::with_budget(budget, || q08::part1_text(text));
+        let _ = cancel::with_budget(budget, || q08::part2_text(text));
+    }
+    let _ = cancel::with_budget(budget, || q08::part1(input_from_bytes(data.to_vec())));
+    let _ = cancel::with_budget(budget, || q08::part2(input_from_bytes(data.to_vec())));
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use aoc::{
+    q10,
+    read_input::{input_from_bytes, input_text},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = input_text(data) {
+        let _ = q10::Map::load_map(text);
+        let _ = q10::part1_text(text);
+        let _ = q10::part2_text(text);
+    }
+    let _ = q10::part1(input_from_bytes(data.to_vec()));
+    let _ = q10::part2(input_from_bytes(data.to_vec()));
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use std::time::Duration;
+
+use aoc::{cancel, q12, read_input::input_from_bytes};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = q12::load_springs(input_from_bytes(data.to_vec()));
+    // part1 is exponential in the number of `?`, so it runs on a budget.
+    let _ = cancel::with_budget(Some(Duration::from_millis(100)), || {
+        q12::part1(input_from_bytes(data.to_vec()))
+    });
+});
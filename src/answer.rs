@@ -0,0 +1,192 @@
+use std::{cmp::Ordering, fmt};
+
+use num::BigInt;
+
+use crate::tsv::Record;
+
+/// What a solver produces. Integers compare by value whatever their width,
+/// so `U64(5)`, `I128(5)` and `Text("5")` are all the same answer; text that
+/// isn't an integer sorts after every integer.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Answer {
+    /// Reads an answer back from its `Display` form, using the narrowest
+    /// integer variant that holds it.
+    pub fn parse(text: &str) -> Answer {
+        let text = text.trim();
+        if !is_integer(text) {
+            Answer::Text(text.to_string())
+        } else if let Ok(value) = text.parse() {
+            Answer::I64(value)
+        } else if let Ok(value) = text.parse() {
+            Answer::U64(value)
+        } else if let Ok(value) = text.parse() {
+            Answer::I128(value)
+        } else if let Ok(value) = text.parse() {
+            Answer::U128(value)
+        } else {
+            text.parse().map_or_else(|_| Answer::Text(text.to_string()), Answer::Big)
+        }
+    }
+
+    /// The answer's value if it is an integer, including integer text.
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Answer::I64(value) => Some(BigInt::from(*value)),
+            Answer::U64(value) => Some(BigInt::from(*value)),
+            Answer::I128(value) => Some(BigInt::from(*value)),
+            Answer::U128(value) => Some(BigInt::from(*value)),
+            Answer::Big(value) => Some(value.clone()),
+            Answer::Text(text) => parse_big(text.trim()),
+        }
+    }
+}
+
+/// Only plain decimal integers count; the standard parsers also take a
+/// leading `+` (and `BigInt`'s underscores), which wouldn't round trip.
+fn is_integer(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn parse_big(text: &str) -> Option<BigInt> {
+    if is_integer(text) {
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::I64(value) => fmt::Display::fmt(value, f),
+            Answer::U64(value) => fmt::Display::fmt(value, f),
+            Answer::I128(value) => fmt::Display::fmt(value, f),
+            Answer::U128(value) => fmt::Display::fmt(value, f),
+            Answer::Big(value) => fmt::Display::fmt(value, f),
+            Answer::Text(text) => f.pad(text),
+        }
+    }
+}
+
+impl Ord for Answer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.to_integer(), other.to_integer()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.to_string().cmp(&other.to_string()),
+        }
+    }
+}
+
+impl PartialOrd for Answer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Answer {}
+
+macro_rules! answer_from {
+    ($($source:ty => $variant:ident as $target:ty),* $(,)?) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Answer {
+                    Answer::$variant(value as $target)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(
+    i32 => I64 as i64,
+    i64 => I64 as i64,
+    isize => I64 as i64,
+    u32 => U64 as u64,
+    u64 => U64 as u64,
+    usize => U64 as u64,
+    i128 => I128 as i128,
+    u128 => U128 as u128,
+);
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Answer {
+        Answer::Big(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl Record for Answer {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [answer] => Some(Answer::parse(answer)),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_compare_by_value() {
+        assert_eq!(Answer::U64(5), Answer::I128(5));
+        assert_eq!(Answer::from("5"), Answer::from(5_usize));
+        assert!(Answer::I64(-1) < Answer::U128(u128::MAX));
+        assert!(Answer::U128(u128::MAX) < Answer::from("ABC"));
+        assert_ne!(Answer::from("abc"), Answer::from("abd"));
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let big = "-123456789012345678901234567890123456789012";
+        for text in ["0", "-7", "18446744073709551615", big, "a b", "+3", "1_000"] {
+            let answer = Answer::parse(text);
+            assert_eq!(answer.to_string(), text);
+        }
+        assert!(matches!(Answer::parse("18446744073709551615"), Answer::U64(_)));
+        assert!(matches!(Answer::parse(big), Answer::Big(_)));
+        assert!(matches!(Answer::parse("+3"), Answer::Text(_)));
+    }
+
+    #[test]
+    fn display_pads_like_the_value() {
+        assert_eq!(format!("{:<6}|", Answer::U64(288)), "288   |");
+        assert_eq!(format!("{:>6}|", Answer::I64(-7)), "    -7|");
+        assert_eq!(format!("{:>6}|", Answer::parse(&"9".repeat(40))).len(), 41);
+        assert_eq!(format!("{:^7}|", Answer::from("abc")), "  abc  |");
+    }
+}
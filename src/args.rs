@@ -0,0 +1,49 @@
+use std::{collections::HashMap, str::FromStr};
+
+/// Command line of the form `aoc <command> [--option value] [--flag]`.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub command: Option<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Args {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                match args.peek() {
+                    Some(value) if !value.starts_with("--") => {
+                        parsed.options.insert(name.to_string(), args.next().unwrap());
+                    }
+                    _ => parsed.flags.push(name.to_string()),
+                }
+            } else if parsed.command.is_none() {
+                parsed.command = Some(arg);
+            }
+        }
+
+        parsed
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|v| v.as_str())
+    }
+
+    pub fn parse_value<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.value(name) {
+            Some(v) => v
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid value for --{}: {}", name, v)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    fs,
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    answer::Answer,
+    read_input::read_input_bytes,
+    runner::{run_solution, select, RunOptions},
+    solutions::Solution,
+};
+
+/// Extension of the sidecar holding an input's expected answers: `alice.txt`
+/// is checked against `alice.answers`, one line per part. An empty line
+/// leaves that part unchecked.
+pub const ANSWERS_EXTENSION: &str = "answers";
+
+enum Outcome {
+    Pass(Duration),
+    Fail {
+        elapsed: Duration,
+        answer: Answer,
+        expected: Answer,
+    },
+    /// No expected answer to compare against.
+    Unchecked(Duration),
+    Error(String),
+}
+
+impl Outcome {
+    fn cell(&self) -> String {
+        match self {
+            Outcome::Pass(elapsed) => format!("pass {:.2?}", elapsed),
+            Outcome::Fail { elapsed, .. } => format!("FAIL {:.2?}", elapsed),
+            Outcome::Unchecked(elapsed) => format!("? {:.2?}", elapsed),
+            Outcome::Error(_) => "ERROR".to_string(),
+        }
+    }
+}
+
+/// Every regular, non-hidden file in `dir` that isn't an answers sidecar.
+pub fn input_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("{}: {}", dir.display(), e))?
+            .path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let sidecar = path.extension().is_some_and(|ext| ext == ANSWERS_EXTENSION);
+        if path.is_file() && !hidden && !sidecar {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+fn expected_answers(input: &Path) -> Result<Vec<Option<Answer>>, String> {
+    let path = input.with_extension(ANSWERS_EXTENSION);
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .map(|line| Some(line.trim()).filter(|answer| !answer.is_empty()).map(Answer::parse))
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn run_one(
+    solution: &Solution,
+    input: &[u8],
+    expected: Option<&Answer>,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let options = RunOptions {
+        no_cache: true,
+        timeout,
+        ..RunOptions::default()
+    };
+
+    match run_solution(solution, input, None, &options) {
+        Ok(result) => match expected {
+            Some(expected) if *expected == result.answer => Outcome::Pass(result.elapsed),
+            Some(expected) => Outcome::Fail {
+                elapsed: result.elapsed,
+                answer: result.answer,
+                expected: expected.clone(),
+            },
+            None => Outcome::Unchecked(result.elapsed),
+        },
+        Err(e) => Outcome::Error(e.to_string()),
+    }
+}
+
+/// Runs the day on every input in `dir` and prints a matrix of inputs by
+/// parts. Fails if any run fails or errors.
+pub fn batch(
+    day: u8,
+    part: Option<u8>,
+    dir: &str,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    batch_to(&mut io::stdout(), day, part, dir, timeout)
+}
+
+/// `batch`, writing the matrix and summary to `out` as the runs finish.
+fn batch_to(
+    out: &mut impl Write,
+    day: u8,
+    part: Option<u8>,
+    dir: &str,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    let mut print = |line: &str| writeln!(out, "{}", line).map_err(|e| format!("output: {}", e));
+    let selected = select(day, part)?;
+    let files = input_files(Path::new(dir))?;
+    if files.is_empty() {
+        return Err(format!("{}: no input files", dir));
+    }
+
+    let names: Vec<String> = files
+        .iter()
+        .map(|path| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    let name_width = names.iter().map(String::len).max().unwrap_or(0).max(5);
+
+    let mut header = format!("{:<width$}", "input", width = name_width);
+    for solution in &selected {
+        header.push_str(&format!("  {:<16}", format!("part {}", solution.part)));
+    }
+    print(header.trim_end())?;
+
+    let mut details = Vec::new();
+    let (mut passed, mut failed, mut errors, mut unchecked) = (0, 0, 0, 0);
+
+    for (path, name) in files.iter().zip(&names) {
+        let input = read_input_bytes(&path.to_string_lossy())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let expected = expected_answers(path)?;
+
+        let mut row = format!("{:<width$}", name, width = name_width);
+        for solution in &selected {
+            let outcome = run_one(
+                solution,
+                &input,
+                expected
+                    .get(solution.part as usize - 1)
+                    .and_then(Option::as_ref),
+                timeout,
+            );
+            row.push_str(&format!("  {:<16}", outcome.cell()));
+
+            match outcome {
+                Outcome::Pass(_) => passed += 1,
+                Outcome::Unchecked(_) => unchecked += 1,
+                Outcome::Fail {
+                    answer, expected, ..
+                } => {
+                    failed += 1;
+                    details.push(format!(
+                        "{} part {}: got {}, expected {}",
+                        name, solution.part, answer, expected
+                    ));
+                }
+                Outcome::Error(e) => {
+                    errors += 1;
+                    details.push(format!("{} part {}: error: {}", name, solution.part, e));
+                }
+            }
+        }
+        print(row.trim_end())?;
+    }
+
+    if !details.is_empty() {
+        print("")?;
+        for detail in &details {
+            print(detail)?;
+        }
+    }
+    print("")?;
+    print(&format!(
+        "{} passed, {} failed, {} errors, {} unchecked",
+        passed, failed, errors, unchecked
+    ))?;
+
+    if failed + errors > 0 {
+        return Err(format!(
+            "day {}: {} of {} runs failed",
+            day,
+            failed + errors,
+            passed + failed + errors + unchecked
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::scratch_dir;
+
+    const EXAMPLE: &str = "Time: 7 15 30\nDistance: 9 40 200\n";
+
+    /// Batches day 6 over `files`, as (name, contents) pairs, returning the
+    /// output lines and the result.
+    fn batch_files(name: &str, files: &[(&str, &str)]) -> (Vec<String>, Result<(), String>) {
+        let dir = scratch_dir(name);
+        for (file, contents) in files {
+            fs::write(dir.join(file), contents).unwrap();
+        }
+
+        let mut out = Vec::new();
+        let result = batch_to(&mut out, 6, None, &dir.to_string_lossy(), None);
+        fs::remove_dir_all(dir).unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        (output.lines().map(str::to_string).collect(), result)
+    }
+
+    #[test]
+    fn runs_every_input_past_errors_and_failures() {
+        let (lines, result) = batch_files(
+            "batch-mixed",
+            &[
+                ("a.txt", EXAMPLE),
+                ("a.answers", "288\n71503\n"),
+                ("b.txt", "Time: 7 x\n"),
+                ("c.txt", EXAMPLE),
+                ("c.answers", "1\n"),
+                (".hidden", "not an input"),
+            ],
+        );
+
+        assert_eq!(lines[0], "input  part 1            part 2");
+        assert!(lines[1].starts_with("a.txt  pass "));
+        assert!(lines[1].contains("  pass "));
+        assert_eq!(lines[2], "b.txt  ERROR             ERROR");
+        assert!(lines[3].starts_with("c.txt  FAIL "));
+        assert!(lines[3].contains("  ? "));
+        assert_eq!(lines[4], "");
+        assert_eq!(
+            lines[5],
+            "b.txt part 1: error: line 2: every race needs a time and a distance"
+        );
+        assert_eq!(
+            lines[6],
+            "b.txt part 2: error: line 1: invalid number \"7x\""
+        );
+        assert_eq!(lines[7], "c.txt part 1: got 288, expected 1");
+        assert_eq!(lines[9], "2 passed, 1 failed, 2 errors, 1 unchecked");
+        assert_eq!(lines.len(), 10);
+        assert_eq!(result, Err("day 6: 3 of 6 runs failed".to_string()));
+    }
+
+    #[test]
+    fn succeeds_when_nothing_fails() {
+        let (lines, result) = batch_files(
+            "batch-clean",
+            &[
+                ("a.txt", EXAMPLE),
+                ("a.answers", "288\n\n"),
+                ("b.txt", EXAMPLE),
+            ],
+        );
+
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("1 passed, 0 failed, 0 errors, 3 unchecked")
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn needs_an_input() {
+        let (lines, result) = batch_files("batch-empty", &[("a.answers", "288\n")]);
+
+        assert!(lines.is_empty());
+        assert!(result.unwrap_err().ends_with(": no input files"));
+    }
+}
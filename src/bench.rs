@@ -0,0 +1,468 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    cancel, crash,
+    error::Result,
+    read_input::{
+        hash_input, input_from_bytes, input_text, map_input, read_input_bytes, read_input_lines,
+    },
+    records::{now, Baseline, BenchResult, BASELINE_PATH, BENCH_PATH, CRASH_DIR},
+    runner::{load_table, panicked, save_table, select},
+    solutions::{self, Solution, SOLUTIONS},
+    tsv::Table,
+};
+
+/// How far a part may slow down from its baseline before `bench` fails.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Slowdown, in percent of the baseline, that counts as a regression.
+    pub max_regression: f64,
+    /// Changes smaller than this are noise whatever their percentage: a part
+    /// that takes microseconds jitters by more than any sensible budget.
+    pub noise: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            max_regression: 10.0,
+            noise: Duration::from_micros(50),
+        }
+    }
+}
+
+/// A median measured against its baseline; changes are in percent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Noise,
+    Faster(f64),
+    Slower(f64),
+    Regressed(f64),
+}
+
+impl Budget {
+    pub fn judge(&self, baseline: Duration, median: Duration) -> Verdict {
+        let change = (median.as_secs_f64() / baseline.as_secs_f64().max(1e-9) - 1.0) * 100.0;
+        if median.abs_diff(baseline) <= self.noise {
+            Verdict::Noise
+        } else if change > self.max_regression {
+            Verdict::Regressed(change)
+        } else if change > 0.0 {
+            Verdict::Slower(change)
+        } else {
+            Verdict::Faster(change)
+        }
+    }
+}
+
+/// What `bench` does with its medians besides recording them.
+#[derive(Debug, Clone, Copy)]
+pub enum BaselineMode {
+    Ignore,
+    Save,
+    Check(Budget),
+}
+
+/// Median of `iterations` timings, each reported by `run` so it can leave
+/// out its own setup.
+fn median(iterations: u32, mut run: impl FnMut() -> Result<Duration>) -> Result<Duration> {
+    let mut timings = Vec::new();
+    for _ in 0..iterations.max(1) {
+        timings.push(run()?);
+    }
+
+    timings.sort();
+    Ok(timings[timings.len() / 2])
+}
+
+/// Runs the solver `iterations` times and returns the median running time.
+/// Each run gets `timeout` to spend and a panic is reported to `crash_dir`
+/// like in `run`, so one bad part can't hang or abort the whole benchmark.
+pub fn measure(
+    solution: &Solution,
+    input: &[u8],
+    iterations: u32,
+    timeout: Option<Duration>,
+    crash_dir: &str,
+) -> Result<Duration> {
+    median(iterations, || {
+        crash::catch(|| cancel::with_budget(timeout, || time(solution, input)))
+            .map_err(|panic| panicked(crash_dir, solution, hash_input(input), panic))?
+    })
+}
+
+/// One run of the solver, timed without splitting the input into lines.
+fn time(solution: &Solution, input: &[u8]) -> Result<Duration> {
+    match solution.solve_text {
+        Some(solve_text) => {
+            let start = Instant::now();
+            solve_text(input_text(input)?)?;
+            Ok(start.elapsed())
+        }
+        None => {
+            let lines = input_from_bytes(input.to_vec());
+            let start = Instant::now();
+            (solution.solve)(lines)?;
+            Ok(start.elapsed())
+        }
+    }
+}
+
+/// Times the parts that can borrow their input both ways, reading the file
+/// included: as owned lines, and memory-mapped with the parser borrowing
+/// from it. The other parts are named as still copying the input.
+pub fn compare_input(
+    day: u8,
+    part: Option<u8>,
+    input_path: &str,
+    iterations: u32,
+) -> std::result::Result<(), String> {
+    for solution in select(day, part)? {
+        let solve_text = match solution.solve_text {
+            Some(solve_text) => solve_text,
+            None => {
+                println!(
+                    "Day {} part {}: copies the input into owned lines, nothing to compare",
+                    day, solution.part
+                );
+                continue;
+            }
+        };
+
+        let owned = median(iterations, || {
+            let start = Instant::now();
+            (solution.solve)(read_input_lines(input_path)?)?;
+            Ok(start.elapsed())
+        });
+        let borrowed = median(iterations, || {
+            let start = Instant::now();
+            let input = map_input(input_path)?;
+            solve_text(input_text(&input)?)?;
+            Ok(start.elapsed())
+        });
+
+        match (owned, borrowed) {
+            (Ok(owned), Ok(borrowed)) => println!(
+                "Day {} part {}: lines {:?}, borrowed {:?} ({:.2}x) over {} runs",
+                day,
+                solution.part,
+                owned,
+                borrowed,
+                owned.as_secs_f64() / borrowed.as_secs_f64().max(1e-9),
+                iterations
+            ),
+            (Err(e), _) | (_, Err(e)) => println!("Day {} part {}: error: {}", day, solution.part, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// The parts to benchmark and their inputs. Without a day that is every part
+/// with a baseline when checking, and otherwise every part whose default
+/// input exists.
+fn targets(
+    day: Option<u8>,
+    part: Option<u8>,
+    input_path: Option<&str>,
+    baselines: &Table<Baseline>,
+    mode: BaselineMode,
+) -> std::result::Result<Vec<(&'static Solution, String)>, String> {
+    if let Some(day) = day {
+        let input = input_path
+            .map(str::to_string)
+            .unwrap_or_else(|| solutions::default_input_path(day));
+        return Ok(select(day, part)?
+            .into_iter()
+            .map(|solution| (solution, input.clone()))
+            .collect());
+    }
+    if input_path.is_some() {
+        return Err("--input needs --day".to_string());
+    }
+
+    let targets: Vec<_> = SOLUTIONS
+        .iter()
+        .filter(|solution| part.is_none_or(|p| p == solution.part))
+        .filter(|solution| match mode {
+            BaselineMode::Check(_) => baselines.get(solution.day, solution.part).is_some(),
+            _ => Path::new(&solutions::default_input_path(solution.day)).exists(),
+        })
+        .map(|solution| (solution, solutions::default_input_path(solution.day)))
+        .collect();
+    if targets.is_empty() {
+        return Err(match mode {
+            BaselineMode::Check(_) => format!("{}: no baselines to check", BASELINE_PATH),
+            _ => "no day has an input to benchmark".to_string(),
+        });
+    }
+
+    Ok(targets)
+}
+
+/// How the median compares to the part's baseline, and whether it regressed;
+/// `Err` if there is no baseline to compare with on this input.
+fn check(
+    baseline: Option<&Baseline>,
+    input_hash: u64,
+    median: Duration,
+    budget: Budget,
+) -> std::result::Result<(String, bool), String> {
+    let baseline = match baseline {
+        Some(baseline) if baseline.input_hash == input_hash => baseline,
+        Some(_) => return Err("baseline was measured on another input".to_string()),
+        None => return Err("no baseline".to_string()),
+    };
+
+    let (note, regressed) = match budget.judge(baseline.median, median) {
+        Verdict::Noise => ("within noise".to_string(), false),
+        Verdict::Faster(change) => (format!("{:+.1}%, faster", change), false),
+        Verdict::Slower(change) => (format!("{:+.1}%, within budget", change), false),
+        Verdict::Regressed(change) => (format!("{:+.1}%, REGRESSED", change), true),
+    };
+    Ok((
+        format!("baseline {:?}, {}", baseline.median, note),
+        regressed,
+    ))
+}
+
+/// Benchmarks the selected parts, recording their medians; depending on
+/// `mode` also saves them as the baseline, or fails naming every part that
+/// slowed down beyond the budget. Parts that fail, or have no baseline to
+/// check against, are named too.
+pub fn bench(
+    day: Option<u8>,
+    part: Option<u8>,
+    input_path: Option<&str>,
+    iterations: u32,
+    timeout: Option<Duration>,
+    mode: BaselineMode,
+) -> std::result::Result<(), String> {
+    let mut results: Table<BenchResult> = load_table(BENCH_PATH)?;
+    let mut baselines: Table<Baseline> = load_table(BASELINE_PATH)?;
+    let mut regressions = Vec::new();
+    let mut failures = Vec::new();
+
+    for (solution, input_path) in targets(day, part, input_path, &baselines, mode)? {
+        let (day, part) = (solution.day, solution.part);
+        let measured = read_input_bytes(&input_path)
+            .map_err(|e| format!("{}: {}", input_path, e))
+            .and_then(|input| {
+                let median = measure(solution, &input, iterations, timeout, CRASH_DIR)
+                    .map_err(|e| e.to_string())?;
+                Ok((hash_input(&input), median))
+            });
+        let (input_hash, median) = match measured {
+            Ok(measured) => measured,
+            Err(e) => {
+                println!("Day {} part {}: error: {}", day, part, e);
+                failures.push(format!("day {} part {}", day, part));
+                continue;
+            }
+        };
+
+        let note = match mode {
+            BaselineMode::Ignore => String::new(),
+            BaselineMode::Save => {
+                baselines.insert(
+                    day,
+                    part,
+                    Baseline {
+                        timestamp: now(),
+                        input_hash,
+                        iterations,
+                        median,
+                    },
+                );
+                ", saved as baseline".to_string()
+            }
+            BaselineMode::Check(budget) => {
+                match check(baselines.get(day, part), input_hash, median, budget) {
+                    Ok((note, regressed)) => {
+                        if regressed {
+                            regressions.push(format!("day {} part {}", day, part));
+                        }
+                        format!(" ({})", note)
+                    }
+                    Err(note) => {
+                        failures.push(format!("day {} part {}", day, part));
+                        format!(" ({})", note)
+                    }
+                }
+            }
+        };
+        println!(
+            "Day {} part {}: median {:?} over {} runs{}",
+            day, part, median, iterations, note
+        );
+        results.insert(
+            day,
+            part,
+            BenchResult {
+                timestamp: now(),
+                iterations,
+                median,
+            },
+        );
+    }
+
+    save_table(&results, BENCH_PATH)?;
+    if let BaselineMode::Save = mode {
+        save_table(&baselines, BASELINE_PATH)?;
+    }
+
+    let mut problems = Vec::new();
+    if let BaselineMode::Check(budget) = mode {
+        if !regressions.is_empty() {
+            problems.push(format!(
+                "regressed by more than {}% over baseline: {}",
+                budget.max_regression,
+                regressions.join(", ")
+            ));
+        }
+    }
+    if !failures.is_empty() {
+        problems.push(format!("failed: {}", failures.join(", ")));
+    }
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{answer::Answer, error::Error, read_input::Input, records::scratch_dir};
+
+    fn spin(_: Input) -> Result<Answer> {
+        loop {
+            cancel::check()?;
+        }
+    }
+
+    fn explode(_: Input) -> Result<Answer> {
+        panic!("boom")
+    }
+
+    fn baseline(input_hash: u64, median_ms: u64) -> Baseline {
+        Baseline {
+            timestamp: 1701406800,
+            input_hash,
+            iterations: 10,
+            median: Duration::from_millis(median_ms),
+        }
+    }
+
+    #[test]
+    fn judge_ignores_noise_and_flags_regressions() {
+        let budget = Budget {
+            max_regression: 10.0,
+            noise: Duration::from_micros(50),
+        };
+        let ms = Duration::from_millis;
+
+        // +100% but only 20µs slower
+        assert_eq!(
+            budget.judge(Duration::from_micros(20), Duration::from_micros(40)),
+            Verdict::Noise
+        );
+        assert!(matches!(budget.judge(ms(10), ms(11)), Verdict::Slower(_)));
+        assert!(matches!(budget.judge(ms(10), ms(12)), Verdict::Regressed(c) if c > 19.0));
+        assert!(matches!(budget.judge(ms(10), ms(5)), Verdict::Faster(_)));
+    }
+
+    #[test]
+    fn baselines_round_trip() {
+        let dir = scratch_dir("bench-baseline");
+        let path = dir.join("baseline.tsv");
+        let mut table: Table<Baseline> = Table::load(&path).unwrap();
+        table.insert(5, 2, baseline(u64::MAX - 1, 3));
+        table.save().unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let loaded: Table<Baseline> = Table::load(&path).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(
+            contents,
+            "5\t2\t1701406800\tfffffffffffffffe\t10\t3000000\n"
+        );
+        let saved = loaded.get(5, 2).unwrap();
+        assert_eq!(
+            (saved.timestamp, saved.input_hash, saved.iterations),
+            (1701406800, u64::MAX - 1, 10)
+        );
+        assert_eq!(saved.median, Duration::from_millis(3));
+        assert!(loaded.get(5, 1).is_none());
+    }
+
+    #[test]
+    fn check_fails_without_a_baseline_on_the_input() {
+        let budget = Budget::default();
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            check(None, 7, ms(10), budget),
+            Err("no baseline".to_string())
+        );
+        assert_eq!(
+            check(Some(&baseline(8, 10)), 7, ms(10), budget),
+            Err("baseline was measured on another input".to_string())
+        );
+        assert!(matches!(
+            check(Some(&baseline(7, 10)), 7, ms(10), budget),
+            Ok((_, false))
+        ));
+        assert!(matches!(
+            check(Some(&baseline(7, 10)), 7, ms(20), budget),
+            Ok((_, true))
+        ));
+    }
+
+    #[test]
+    fn measure_stops_a_slow_part_and_catches_a_panic() {
+        let dir = scratch_dir("bench-measure");
+        let crashes = dir.to_string_lossy();
+        let day = 98;
+        let slow = Solution {
+            day,
+            part: 1,
+            version: "1",
+            solve: spin,
+            solve_text: None,
+        };
+        let broken = Solution {
+            day,
+            part: 2,
+            version: "1",
+            solve: explode,
+            solve_text: None,
+        };
+
+        assert!(matches!(
+            measure(&slow, b"", 3, Some(Duration::ZERO), &crashes),
+            Err(Error::TimedOut(_))
+        ));
+        assert!(matches!(
+            measure(&broken, b"", 3, None, &crashes),
+            Err(Error::Panicked { .. })
+        ));
+
+        let reports: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        let report = fs::read_to_string(&reports[0]).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].to_string_lossy().contains("day98-part2-"));
+        assert!(report.contains("message: boom"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
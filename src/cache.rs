@@ -0,0 +1,161 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    answer::Answer,
+    tsv::{read_rows, write_rows},
+};
+
+pub const CACHE_PATH: &str = ".aoc/cache.tsv";
+
+#[derive(Debug)]
+struct Entry {
+    day: u8,
+    part: u8,
+    version: String,
+    input_hash: u64,
+    answer: Answer,
+}
+
+impl Entry {
+    fn parse(row: &[String]) -> Option<Entry> {
+        match row {
+            [day, part, version, input_hash, answer] => Some(Entry {
+                day: day.parse().ok()?,
+                part: part.parse().ok()?,
+                version: version.clone(),
+                input_hash: u64::from_str_radix(input_hash, 16).ok()?,
+                answer: Answer::parse(answer),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.day.to_string(),
+            self.part.to_string(),
+            self.version.clone(),
+            format!("{:016x}", self.input_hash),
+            self.answer.to_string(),
+        ]
+    }
+}
+
+/// Answers keyed by (day, part, solver version, input hash), persisted as one
+/// tab separated entry per line.
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl Cache {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Cache> {
+        let path = path.as_ref().to_path_buf();
+        let entries = read_rows(&path)?
+            .iter()
+            .filter_map(|row| Entry::parse(row))
+            .collect();
+
+        Ok(Cache { path, entries })
+    }
+
+    pub fn get(&self, day: u8, part: u8, version: &str, input_hash: u64) -> Option<&Answer> {
+        self.entries
+            .iter()
+            .find(|e| {
+                e.day == day && e.part == part && e.version == version && e.input_hash == input_hash
+            })
+            .map(|e| &e.answer)
+    }
+
+    /// Stores an answer, dropping the previous answer for the same input and
+    /// every answer produced by another version of the solver.
+    pub fn insert(&mut self, day: u8, part: u8, version: &str, input_hash: u64, answer: Answer) {
+        self.entries.retain(|e| {
+            e.day != day || e.part != part || (e.version == version && e.input_hash != input_hash)
+        });
+        self.entries.push(Entry {
+            day,
+            part,
+            version: version.to_string(),
+            input_hash,
+            answer,
+        });
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_rows(&self.path, self.entries.iter().map(Entry::to_row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::records::scratch_dir;
+
+    /// A cache that is never saved.
+    fn empty() -> Cache {
+        Cache {
+            path: PathBuf::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    fn answer(cache: &Cache, version: &str, input_hash: u64) -> Option<String> {
+        cache.get(5, 1, version, input_hash).map(Answer::to_string)
+    }
+
+    #[test]
+    fn hits_only_the_same_input() {
+        let mut cache = empty();
+        cache.insert(5, 1, "2", 0xaa, Answer::from(35_u64));
+
+        assert_eq!(answer(&cache, "2", 0xaa).as_deref(), Some("35"));
+        assert_eq!(answer(&cache, "2", 0xbb), None);
+        assert_eq!(cache.get(5, 2, "2", 0xaa), None);
+    }
+
+    #[test]
+    fn keeps_other_inputs_and_drops_other_versions() {
+        let mut cache = empty();
+        cache.insert(5, 1, "2", 0xaa, Answer::from(35_u64));
+        cache.insert(5, 1, "2", 0xbb, Answer::from(36_u64));
+        cache.insert(5, 1, "2", 0xaa, Answer::from(37_u64));
+
+        assert_eq!(answer(&cache, "2", 0xaa).as_deref(), Some("37"));
+        assert_eq!(answer(&cache, "2", 0xbb).as_deref(), Some("36"));
+
+        // a new solver version invalidates every answer of the old one
+        assert_eq!(answer(&cache, "3", 0xaa), None);
+        cache.insert(5, 1, "3", 0xaa, Answer::from(35_u64));
+        assert_eq!(answer(&cache, "2", 0xaa), None);
+        assert_eq!(answer(&cache, "2", 0xbb), None);
+        assert_eq!(answer(&cache, "3", 0xaa).as_deref(), Some("35"));
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let dir = scratch_dir("cache-round-trip");
+        let path = dir.join("cache.tsv");
+        let mut cache = Cache::load(&path).unwrap();
+        cache.insert(5, 1, "2", u64::MAX, Answer::from(35_u64));
+        cache.insert(12, 2, "1", 7, Answer::from("text answer"));
+        cache.save().unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let loaded = Cache::load(&path).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+
+        assert!(contents.starts_with("5\t1\t2\tffffffffffffffff\t35\n"));
+        assert_eq!(answer(&loaded, "2", u64::MAX).as_deref(), Some("35"));
+        assert_eq!(
+            loaded.get(12, 2, "1", 7).map(Answer::to_string).as_deref(),
+            Some("text answer")
+        );
+    }
+}
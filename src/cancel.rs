@@ -0,0 +1,64 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use crate::error::{Error, Result};
+
+/// The clock is only read once every this many `check` calls.
+const CHECK_INTERVAL: u32 = 1024;
+
+#[derive(Clone, Copy)]
+struct Budget {
+    deadline: Instant,
+    budget: Duration,
+}
+
+thread_local! {
+    static BUDGET: Cell<Option<Budget>> = const { Cell::new(None) };
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Restores the enclosing budget when `with_budget` returns or unwinds.
+struct Restore(Option<Budget>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        BUDGET.with(|budget| budget.set(self.0));
+    }
+}
+
+/// Runs `f` on this thread with `budget` to spend; `None`, or a budget too
+/// large for the clock to reach, means no limit. Solvers notice the budget
+/// running out through `check`.
+pub fn with_budget<T>(budget: Option<Duration>, f: impl FnOnce() -> T) -> T {
+    let limit = budget.and_then(|budget| {
+        Some(Budget {
+            deadline: Instant::now().checked_add(budget)?,
+            budget,
+        })
+    });
+    let _restore = Restore(BUDGET.with(|current| current.replace(limit)));
+    CALLS.with(|calls| calls.set(0));
+
+    f()
+}
+
+/// Cancellation point for solvers' hot loops: fails with `Error::TimedOut`
+/// once the budget given to `with_budget` is spent. Cheap enough to call on
+/// every iteration.
+pub fn check() -> Result<()> {
+    let calls = CALLS.with(|calls| {
+        let next = calls.get().wrapping_add(1);
+        calls.set(next);
+        next
+    });
+    if !calls.is_multiple_of(CHECK_INTERVAL) {
+        return Ok(());
+    }
+
+    match BUDGET.with(Cell::get) {
+        Some(limit) if Instant::now() >= limit.deadline => Err(Error::TimedOut(limit.budget)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,62 @@
+use std::ops::{Add, Mul, Sub};
+
+use num::{traits::AsPrimitive, CheckedAdd, CheckedMul, CheckedSub};
+
+use crate::error::{Error, Result};
+
+/// Whether the solvers' counters, steps and conversions are checked. Sums
+/// that can overflow on a real input are checked regardless.
+pub const ENABLED: bool = cfg!(feature = "checked-arithmetic");
+
+/// `a + b`; with `checked-arithmetic` an overflow fails with `operation`
+/// instead of panicking (debug) or wrapping (release).
+pub fn add<T: CheckedAdd + Add<Output = T> + Copy>(
+    a: T,
+    b: T,
+    operation: &'static str,
+) -> Result<T> {
+    if ENABLED {
+        a.checked_add(&b).ok_or(Error::Overflow(operation))
+    } else {
+        Ok(a + b)
+    }
+}
+
+/// `a - b`, checked like `add`.
+pub fn sub<T: CheckedSub + Sub<Output = T> + Copy>(
+    a: T,
+    b: T,
+    operation: &'static str,
+) -> Result<T> {
+    if ENABLED {
+        a.checked_sub(&b).ok_or(Error::Overflow(operation))
+    } else {
+        Ok(a - b)
+    }
+}
+
+/// `a * b`, checked like `add`.
+pub fn mul<T: CheckedMul + Mul<Output = T> + Copy>(
+    a: T,
+    b: T,
+    operation: &'static str,
+) -> Result<T> {
+    if ENABLED {
+        a.checked_mul(&b).ok_or(Error::Overflow(operation))
+    } else {
+        Ok(a * b)
+    }
+}
+
+/// `value as U`; with `checked-arithmetic` a value that doesn't fit fails
+/// with `operation` instead of being truncated.
+pub fn cast<T: AsPrimitive<U>, U: TryFrom<T> + Copy + 'static>(
+    value: T,
+    operation: &'static str,
+) -> Result<U> {
+    if ENABLED {
+        U::try_from(value).map_err(|_| Error::Overflow(operation))
+    } else {
+        Ok(value.as_())
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::Answer,
+    batch::input_files,
+    read_input::read_input_bytes,
+    runner::{run_solution, select, RunOptions},
+    tsv::read_rows,
+};
+
+/// How often a running external solver is polled for its exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Another implementation of a day: `command` runs through `sh -c` with the
+/// input on stdin and prints one answer per line, part 1 first.
+#[derive(Debug, Clone)]
+pub struct External {
+    pub name: String,
+    /// `None` for a solver configured with `*`, which handles every day.
+    pub day: Option<u8>,
+    pub command: String,
+}
+
+/// Reads `name<TAB>day<TAB>command` rows, skipping `#` comments, and keeps
+/// the solvers for `day`.
+pub fn load_externals(path: &str, day: u8) -> Result<Vec<External>, String> {
+    let rows = read_rows(Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut externals = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if row[0].starts_with('#') {
+            continue;
+        }
+        let external =
+            match &row[..] {
+                [name, days, command] => External {
+                    name: name.clone(),
+                    day: match days.as_str() {
+                        "*" => None,
+                        days => Some(days.parse().map_err(|_| {
+                            format!("{}: row {}: invalid day {}", path, i + 1, days)
+                        })?),
+                    },
+                    command: command.clone(),
+                },
+                _ => {
+                    return Err(format!(
+                        "{}: row {}: expected name, day and command separated by tabs",
+                        path,
+                        i + 1
+                    ))
+                }
+            };
+        if external.day.is_none_or(|d| d == day) {
+            externals.push(external);
+        }
+    }
+
+    Ok(externals)
+}
+
+/// An external solver's answers for one input, one per output line.
+#[derive(Debug)]
+struct ExternalRun {
+    answers: Vec<Option<Answer>>,
+    elapsed: Duration,
+}
+
+/// Kills a timed out solver. On unix it leads its own process group and the
+/// whole group goes, so nothing it started keeps the output pipes open;
+/// elsewhere only the shell is killed, and a child still holding the pipes
+/// keeps the run waiting until it exits.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Runs `command` on `input`, killing it once `timeout` passes. The time
+/// includes starting the process.
+fn run_external(
+    command: &str,
+    input: &[u8],
+    timeout: Option<Duration>,
+) -> Result<ExternalRun, String> {
+    let start = Instant::now();
+    let mut command_line = Command::new("sh");
+    command_line.arg("-c").arg(command);
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command_line, 0);
+    let mut child = command_line
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start: {}", e))?;
+
+    // feed and drain the pipes on their own threads so neither side blocks
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_vec();
+    let writer = thread::spawn(move || {
+        // a solver that exits without reading everything closes the pipe
+        let _ = stdin.write_all(&input);
+    });
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let errors = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stderr.read_to_string(&mut output);
+        output
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break Ok(status);
+        }
+        if let Some(timeout) = timeout {
+            if start.elapsed() >= timeout {
+                kill(&mut child);
+                break Err(timeout);
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let elapsed = start.elapsed();
+
+    // with the solver gone its pipes close, so the threads finish too
+    let _ = writer.join();
+    let output = reader
+        .join()
+        .map_err(|_| "stdout reader panicked".to_string())?
+        .map_err(|e| e.to_string())?;
+    let errors = errors.join().unwrap_or_default();
+    let status =
+        status.map_err(|timeout| format!("timed out after {} s", timeout.as_secs_f64()))?;
+    if !status.success() {
+        let reason = errors.lines().next().unwrap_or("no output on stderr");
+        return Err(format!("{}: {}", status, reason));
+    }
+
+    let answers = String::from_utf8_lossy(&output)
+        .lines()
+        .map(|line| {
+            Some(line.trim())
+                .filter(|line| !line.is_empty())
+                .map(Answer::parse)
+        })
+        .collect();
+
+    Ok(ExternalRun { answers, elapsed })
+}
+
+#[derive(Default)]
+struct Tally {
+    agreed: usize,
+    differed: usize,
+    errors: usize,
+    /// Their time over ours, per input where both finished.
+    ratios: Vec<f64>,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Runs our solver and every configured external one on each input, listing
+/// each part's answers and how long the others took relative to ours. Fails
+/// if any external solver disagreed or failed.
+pub fn compare(
+    day: u8,
+    part: Option<u8>,
+    inputs: &[PathBuf],
+    config: &str,
+    timeout: Option<Duration>,
+) -> Result<(), String> {
+    let selected = select(day, part)?;
+    let externals = load_externals(config, day)?;
+    if externals.is_empty() {
+        return Err(format!(
+            "{}: no external solvers configured for day {}",
+            config, day
+        ));
+    }
+
+    let name_width = externals
+        .iter()
+        .map(|e| e.name.len())
+        .chain([3])
+        .max()
+        .unwrap_or(3);
+    let options = RunOptions {
+        no_cache: true,
+        timeout,
+        ..RunOptions::default()
+    };
+    let mut tallies: Vec<Tally> = externals.iter().map(|_| Tally::default()).collect();
+    let mut disagreements = Vec::new();
+
+    for path in inputs {
+        let name = file_name(path);
+        let input = read_input_bytes(&path.to_string_lossy())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let mut header = format!("{}\n  {:<width$}", name, "", width = name_width);
+        for solution in &selected {
+            header.push_str(&format!("  {:<24}", format!("part {}", solution.part)));
+        }
+        println!("{}", header.trim_end());
+
+        // our parts run separately; an external solver does all of them in
+        // one process, so ratios compare against our total
+        let ours: Vec<_> = selected
+            .iter()
+            .map(|solution| run_solution(solution, &input, None, &options))
+            .collect();
+        let our_total: Option<Duration> = ours
+            .iter()
+            .map(|outcome| outcome.as_ref().ok().map(|result| result.elapsed))
+            .sum();
+        let mut row = format!("  {:<width$}", "aoc", width = name_width);
+        for outcome in &ours {
+            let cell = match outcome {
+                Ok(result) => result.answer.to_string(),
+                Err(_) => "ERROR".to_string(),
+            };
+            row.push_str(&format!("  {:<24}", cell));
+        }
+        if let Some(total) = our_total {
+            row.push_str(&format!("  {:.2?}", total));
+        }
+        println!("{}", row.trim_end());
+        for (solution, outcome) in selected.iter().zip(&ours) {
+            if let Err(e) = outcome {
+                println!("    part {}: error: {}", solution.part, e);
+            }
+        }
+
+        for (external, tally) in externals.iter().zip(&mut tallies) {
+            let mut row = format!("  {:<width$}", external.name, width = name_width);
+            let run = match run_external(&external.command, &input, timeout) {
+                Ok(run) => run,
+                Err(e) => {
+                    tally.errors += 1;
+                    println!("{}  error: {}", row, e);
+                    continue;
+                }
+            };
+
+            for (solution, outcome) in selected.iter().zip(&ours) {
+                let answer = match run.answers.get(solution.part as usize - 1) {
+                    Some(Some(answer)) => answer,
+                    _ => {
+                        tally.errors += 1;
+                        row.push_str(&format!("  {:<24}", "(no answer)"));
+                        continue;
+                    }
+                };
+                let verdict = match outcome {
+                    Ok(result) if *answer == result.answer => {
+                        tally.agreed += 1;
+                        "agrees"
+                    }
+                    Ok(result) => {
+                        tally.differed += 1;
+                        disagreements.push(format!(
+                            "{} part {}: {} says {}, aoc says {}",
+                            name, solution.part, external.name, answer, result.answer
+                        ));
+                        "DIFFERS"
+                    }
+                    Err(_) => "unchecked",
+                };
+                row.push_str(&format!("  {:<24}", format!("{} {}", answer, verdict)));
+            }
+
+            row.push_str(&format!("  {:.2?}", run.elapsed));
+            if let Some(total) = our_total {
+                let ratio = run.elapsed.as_secs_f64() / total.as_secs_f64().max(1e-9);
+                tally.ratios.push(ratio);
+                row.push_str(&format!(" ({:.1}x)", ratio));
+            }
+            println!("{}", row);
+        }
+    }
+
+    if !disagreements.is_empty() {
+        println!();
+        for disagreement in &disagreements {
+            println!("{}", disagreement);
+        }
+    }
+    println!();
+    for (external, tally) in externals.iter().zip(&tallies) {
+        let mut ratios = tally.ratios.clone();
+        ratios.sort_by(f64::total_cmp);
+        let median = match ratios.get(ratios.len() / 2) {
+            Some(ratio) => format!(", median {:.1}x our time", ratio),
+            None => String::new(),
+        };
+        println!(
+            "{}: {} agree, {} differ, {} errors{}",
+            external.name, tally.agreed, tally.differed, tally.errors, median
+        );
+    }
+
+    let failed: usize = tallies.iter().map(|t| t.differed + t.errors).sum();
+    if failed > 0 {
+        return Err(format!(
+            "day {}: {} external runs disagreed or failed",
+            day, failed
+        ));
+    }
+
+    Ok(())
+}
+
+/// The inputs `compare` runs on: every file in `dir`, or just `input`.
+pub fn inputs(dir: Option<&str>, input: &str) -> Result<Vec<PathBuf>, String> {
+    match dir {
+        Some(dir) => {
+            let files = input_files(Path::new(dir))?;
+            if files.is_empty() {
+                return Err(format!("{}: no input files", dir));
+            }
+            Ok(files)
+        }
+        None => Ok(vec![PathBuf::from(input)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    /// Writes `contents` to a config file of its own and loads it for `day`.
+    fn load(name: &str, contents: &str, day: u8) -> Result<Vec<External>, String> {
+        let path = env::temp_dir().join(format!("aoc-compare-{}-{}.tsv", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        let externals = load_externals(&path.to_string_lossy(), day);
+        fs::remove_file(&path).unwrap();
+        externals
+    }
+
+    #[test]
+    fn loads_solvers_for_the_day() {
+        let config = "# name\tday\tcommand\npy\t5\tpython3 q05.py\nall\t*\t./solve --day $DAY\nother\t6\tcat\n";
+        let externals = load("day", config, 5).unwrap();
+        let loaded: Vec<(&str, Option<u8>, &str)> = externals
+            .iter()
+            .map(|e| (e.name.as_str(), e.day, e.command.as_str()))
+            .collect();
+
+        assert_eq!(
+            loaded,
+            [
+                ("py", Some(5), "python3 q05.py"),
+                ("all", None, "./solve --day $DAY")
+            ]
+        );
+        assert!(load("empty", "", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_rows() {
+        let error = load("columns", "# ok\npy 5 python3 q05.py\n", 5).unwrap_err();
+        assert!(error.ends_with("row 2: expected name, day and command separated by tabs"));
+
+        let error = load("day", "py\tfive\tpython3 q05.py\n", 5).unwrap_err();
+        assert!(error.ends_with("row 1: invalid day five"));
+    }
+
+    #[test]
+    fn reads_one_answer_per_line() {
+        let run = run_external("echo 1; echo; echo 2", b"", None).unwrap();
+        assert_eq!(
+            run.answers,
+            [Some(Answer::from(1_u64)), None, Some(Answer::from(2_u64))]
+        );
+
+        let run = run_external("wc -l", b"a\nb\nc\n", Some(Duration::from_secs(5))).unwrap();
+        assert_eq!(run.answers, [Some(Answer::from(3_u64))]);
+    }
+
+    #[test]
+    fn reports_a_failing_solver() {
+        let error = run_external("echo oops >&2; exit 3", b"", None).unwrap_err();
+        assert!(error.contains('3'), "{}", error);
+        assert!(error.ends_with(": oops"), "{}", error);
+    }
+
+    #[test]
+    fn kills_a_slow_solver_and_its_children() {
+        // the background sleep inherits stdout; if it outlived the kill, the
+        // reader would wait for it to finish
+        let start = Instant::now();
+        let error =
+            run_external("sleep 30 & sleep 30", b"", Some(Duration::from_millis(100))).unwrap_err();
+
+        assert_eq!(error, "timed out after 0.1 s");
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
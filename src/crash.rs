@@ -0,0 +1,102 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Once,
+};
+
+use crate::{history::git_revision, records::now, solutions::Solution};
+
+/// What a caught panic left behind.
+#[derive(Debug, Clone)]
+pub struct Panic {
+    pub message: String,
+    /// `file:line:column` of the panic, when the hook saw it.
+    pub location: Option<String>,
+}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<Panic>> = const { RefCell::new(None) };
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+/// Wraps the panic hook so panics inside `catch` are recorded instead of
+/// printed; panics anywhere else still reach the previous hook.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(Cell::get) {
+                let panic = Panic {
+                    message: payload_message(info.payload()),
+                    location: info.location().map(|l| l.to_string()),
+                };
+                LAST_PANIC.with(|last| *last.borrow_mut() = Some(panic));
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Runs `f`, turning a panic into `Err` instead of unwinding further.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Panic> {
+    install_hook();
+
+    let was_catching = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(was_catching));
+
+    result.map_err(|payload| {
+        LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| Panic {
+                message: payload_message(payload.as_ref()),
+                location: None,
+            })
+    })
+}
+
+/// Writes a report for a panic in `solution` to its own file under `dir`,
+/// normally `CRASH_DIR`, and returns the file's path.
+pub fn report(
+    dir: impl AsRef<Path>,
+    solution: &Solution,
+    input_hash: u64,
+    panic: &Panic,
+) -> std::io::Result<PathBuf> {
+    let timestamp = now();
+    let path = dir.as_ref().join(format!(
+        "day{:02}-part{}-{}-{:016x}.txt",
+        solution.day, solution.part, timestamp, input_hash
+    ));
+
+    let contents = format!(
+        "day: {}\npart: {}\nsolver version: {}\nrevision: {}\ntimestamp: {}\ninput hash: {:016x}\nlocation: {}\nmessage: {}\n",
+        solution.day,
+        solution.part,
+        solution.version,
+        git_revision(),
+        timestamp,
+        input_hash,
+        panic.location.as_deref().unwrap_or("unknown"),
+        panic.message
+    );
+    fs::create_dir_all(dir)?;
+    fs::write(&path, contents)?;
+
+    Ok(path)
+}
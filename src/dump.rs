@@ -0,0 +1,166 @@
+use serde_json::Value;
+
+use crate::{error::Result, q03, q04, q05, q06, q07, q08, q09, q10, q11, q12, read_input::Input};
+
+/// Builds a day's parsed model as JSON, so parses can be diffed between
+/// versions or handed to other tools.
+pub type Dumper = fn(Input) -> Result<Value>;
+
+pub fn dumper(day: u8) -> Option<Dumper> {
+    match day {
+        3 => Some(q03::dump),
+        4 => Some(q04::dump),
+        5 => Some(q05::dump),
+        6 => Some(q06::dump),
+        7 => Some(q07::dump),
+        8 => Some(q08::dump),
+        9 => Some(q09::dump),
+        10 => Some(q10::dump),
+        11 => Some(q11::dump),
+        12 => Some(q12::dump),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::read_input::input_from_bytes;
+
+    fn dump(day: u8, example: &str) -> Value {
+        let dumper = dumper(day).unwrap();
+        dumper(input_from_bytes(example.as_bytes().to_vec())).unwrap()
+    }
+
+    #[test]
+    fn day03() {
+        let model = dump(3, include_str!("../tests/fixtures/day03.txt"));
+
+        assert_eq!(model["part_numbers"].as_array().unwrap().len(), 10);
+        assert_eq!(
+            model["part_numbers"][0],
+            json!({ "number": 467, "at": [1, 1] })
+        );
+        assert_eq!(model["gears"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            model["gears"][0],
+            json!({ "at": [2, 4], "part_numbers": [467, 35] })
+        );
+    }
+
+    #[test]
+    fn day04() {
+        let model = dump(4, include_str!("../tests/fixtures/day04.txt"));
+        let cards = model["cards"].as_array().unwrap();
+
+        assert_eq!(cards.len(), 6);
+        assert_eq!(cards[5]["card"], 6);
+        for card in cards {
+            assert_eq!(card["winning"].as_array().unwrap().len(), 5);
+            assert_eq!(card["numbers"].as_array().unwrap().len(), 8);
+        }
+        assert_eq!(cards[0]["winning"], json!([41, 48, 83, 86, 17]));
+        let matches: Vec<_> = cards.iter().map(|card| card["matches"].clone()).collect();
+        assert_eq!(matches, [4, 2, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn day05() {
+        let model = dump(5, include_str!("../tests/fixtures/day05.txt"));
+        let sizes: Vec<usize> = model["maps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|map| map.as_array().unwrap().len())
+            .collect();
+
+        assert_eq!(model["seeds"], json!([79, 14, 55, 13]));
+        assert_eq!(sizes, [2, 3, 4, 2, 3, 2, 2]);
+        assert_eq!(
+            model["maps"][0][0],
+            json!({ "destination": 50, "source": 98, "length": 2 })
+        );
+    }
+
+    #[test]
+    fn day06() {
+        let model = dump(6, include_str!("../tests/fixtures/day06.txt"));
+
+        assert_eq!(model["races"].as_array().unwrap().len(), 3);
+        assert_eq!(model["races"][2], json!({ "time": 30, "distance": 200 }));
+        assert_eq!(
+            model["kerned_race"],
+            json!({ "time": 71530, "distance": 940200 })
+        );
+    }
+
+    #[test]
+    fn day07() {
+        let model = dump(7, include_str!("../tests/fixtures/day07.txt"));
+
+        assert_eq!(model["hands"].as_array().unwrap().len(), 5);
+        assert_eq!(
+            model["hands"][1],
+            json!({
+                "cards": "T55J5",
+                "bid": 684,
+                "type": "ThreeOfAKind",
+                "joker_type": "FourOfAKind",
+            })
+        );
+    }
+
+    #[test]
+    fn day08() {
+        let model = dump(8, include_str!("../tests/fixtures/day08.txt"));
+
+        assert_eq!(model["directions"], "LR");
+        assert_eq!(model["nodes"].as_object().unwrap().len(), 10);
+        assert_eq!(model["nodes"]["11A"], json!(["11B", "XXX"]));
+    }
+
+    #[test]
+    fn day09() {
+        let model = dump(9, include_str!("../tests/fixtures/day09.txt"));
+
+        assert_eq!(model["histories"].as_array().unwrap().len(), 3);
+        assert_eq!(model["histories"][0], json!([0, 3, 6, 9, 12, 15]));
+    }
+
+    #[test]
+    fn day10() {
+        let model = dump(10, include_str!("../tests/fixtures/day10.txt"));
+
+        assert_eq!(
+            (&model["width"], &model["height"]),
+            (&json!(20), &json!(10))
+        );
+        assert_eq!(model["start"], json!([1, 5]));
+        assert_eq!(model["grid"][0], "FF7FSF7F7F7F7F7F---7");
+        assert_eq!(model["loop_length"], 160);
+        assert_eq!(model["main_loop"].as_array().unwrap().len(), 160);
+    }
+
+    #[test]
+    fn day11() {
+        let model = dump(11, include_str!("../tests/fixtures/day11.txt"));
+
+        assert_eq!(model["galaxies"].as_array().unwrap().len(), 9);
+        assert_eq!(model["galaxies"][0], json!([1, 4]));
+        assert_eq!(model["empty_rows"], json!([4, 8]));
+        assert_eq!(model["empty_columns"], json!([3, 6, 9]));
+    }
+
+    #[test]
+    fn day12() {
+        let model = dump(12, include_str!("../tests/fixtures/day12.txt"));
+
+        assert_eq!(model["records"].as_array().unwrap().len(), 6);
+        assert_eq!(
+            model["records"][0],
+            json!({ "springs": "???.###", "broken": [1, 1, 3] })
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fmt, io, path::PathBuf, time::Duration};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    InvalidNumber(String),
+    MissingSeparator(char),
+    UnexpectedChar(char),
+    RaggedRow { expected: usize, found: usize },
+    /// Day specific problems that don't fit the shared kinds.
+    Other(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::InvalidNumber(token) => write!(f, "invalid number {:?}", token),
+            ParseErrorKind::MissingSeparator(separator) => {
+                write!(f, "missing separator {:?}", separator)
+            }
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::RaggedRow { expected, found } => {
+                write!(f, "row is {} wide, expected {}", found, expected)
+            }
+            ParseErrorKind::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// Malformed input; `line` is 1-based.
+    Parse { line: usize, kind: ParseErrorKind },
+    Overflow(&'static str),
+    /// The input is well formed but has no answer, e.g. an unreachable goal.
+    NoSolution(String),
+    /// The solver gave up at a cancellation point after spending its budget.
+    TimedOut(Duration),
+    /// The solver panicked; `report` is the crash report written for it.
+    Panicked {
+        message: String,
+        location: Option<String>,
+        report: Option<PathBuf>,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn parse(line: usize, message: impl Into<String>) -> Error {
+        Error::Parse {
+            line,
+            kind: ParseErrorKind::Other(message.into()),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse { line, kind } => write!(f, "line {}: {}", line, kind),
+            Error::Overflow(operation) => write!(f, "overflow in {}", operation),
+            Error::NoSolution(reason) => write!(f, "no solution: {}", reason),
+            Error::TimedOut(budget) => write!(f, "timed out after {} s", budget.as_secs_f64()),
+            Error::Panicked {
+                message,
+                location,
+                report,
+            } => {
+                write!(f, "panicked")?;
+                if let Some(location) = location {
+                    write!(f, " at {}", location)?;
+                }
+                write!(f, ": {}", message)?;
+                match report {
+                    Some(report) => write!(f, " (crash report {})", report.display()),
+                    None => write!(f, " (no crash report written)"),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::TcpStream,
+};
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidInput, message.into())
+}
+
+/// Minimal HTTP/1.0 GET for plain `http://` URLs, enough to pull files from a
+/// local mirror or stub server. Sends the session cookie when one is given.
+pub fn http_get(url: &str, session: Option<&str>) -> io::Result<Vec<u8>> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        invalid(format!(
+            "{}: only http:// URLs are supported, save the file and pass it with --file",
+            url
+        ))
+    })?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut stream = TcpStream::connect(addr)?;
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\n", path, host)?;
+    if let Some(session) = session {
+        write!(stream, "Cookie: session={}\r\n", session)?;
+    }
+    write!(stream, "\r\n")?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| invalid(format!("{}: malformed response", url)))?;
+    let status_line = String::from_utf8_lossy(&response[..header_end]);
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    if status != "200" {
+        return Err(io::Error::other(format!("{}: HTTP status {}", url, status)));
+    }
+
+    Ok(response.split_off(header_end + 4))
+}
@@ -0,0 +1,368 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Weighted adjacency lists, built once from a neighbour closure.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    edges: HashMap<N, Vec<(N, u64)>>,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    /// Explores every node reachable from `starts`, asking `neighbours` for
+    /// the weighted edges out of each one.
+    pub fn from_fn<I>(
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+    ) -> Graph<N>
+    where
+        I: IntoIterator<Item = (N, u64)>,
+    {
+        let mut edges = HashMap::new();
+        let mut pending: Vec<N> = starts.into_iter().collect();
+
+        while let Some(node) = pending.pop() {
+            if edges.contains_key(&node) {
+                continue;
+            }
+            let out: Vec<(N, u64)> = neighbours(&node).into_iter().collect();
+            pending.extend(out.iter().map(|(next, _)| next.clone()));
+            edges.insert(node, out);
+        }
+
+        Graph { edges }
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N, u64)>) -> Graph<N> {
+        let mut graph = Graph {
+            edges: HashMap::new(),
+        };
+        for (from, to, weight) in edges {
+            graph.edges.entry(to.clone()).or_default();
+            graph.edges.entry(from).or_default().push((to, weight));
+        }
+
+        graph
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    pub fn edges(&self, node: &N) -> impl Iterator<Item = (N, u64)> + '_ {
+        self.edges.get(node).into_iter().flatten().cloned()
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = N> + '_ {
+        self.edges(node).map(|(next, _)| next)
+    }
+}
+
+/// Number of edges from `start` to every reachable node.
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+        for next in neighbours(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Cheapest path from `start` to a node satisfying `is_goal`, with its cost.
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// Dijkstra guided by `heuristic`, which must never overestimate the
+/// remaining cost for the returned path to be the cheapest.
+pub fn astar<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    // Nodes are numbered so the heap doesn't need `N: Ord`.
+    let mut ids: HashMap<N, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start.clone()];
+    let mut costs: Vec<u64> = vec![0];
+    let mut parents: Vec<Option<usize>> = vec![None];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0_u64, 0_usize))]);
+
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        if cost > costs[id] {
+            continue;
+        }
+        if is_goal(&nodes[id]) {
+            let mut path = vec![nodes[id].clone()];
+            let mut current = id;
+            while let Some(parent) = parents[current] {
+                path.push(nodes[parent].clone());
+                current = parent;
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        for (next, weight) in neighbours(&nodes[id].clone()) {
+            let next_cost = cost.checked_add(weight)?;
+            let next_id = match ids.get(&next) {
+                Some(&next_id) if costs[next_id] <= next_cost => continue,
+                Some(&next_id) => next_id,
+                None => {
+                    ids.insert(next.clone(), nodes.len());
+                    nodes.push(next.clone());
+                    costs.push(u64::MAX);
+                    parents.push(None);
+                    nodes.len() - 1
+                }
+            };
+            costs[next_id] = next_cost;
+            parents[next_id] = Some(id);
+            heap.push(Reverse((
+                next_cost.saturating_add(heuristic(&next)),
+                next_cost,
+                next_id,
+            )));
+        }
+    }
+
+    None
+}
+
+/// Where a walk that follows a single successor per node starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Steps taken before first entering the cycle.
+    pub prefix: usize,
+    pub length: usize,
+}
+
+/// Follows `next` from `start` until a node repeats; `None` if the walk ends.
+pub fn find_cycle<N>(start: N, mut next: impl FnMut(&N) -> Option<N>) -> Option<Cycle>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut seen: HashMap<N, usize> = HashMap::new();
+    let mut node = start;
+
+    for step in 0.. {
+        if let Some(&first) = seen.get(&node) {
+            return Some(Cycle {
+                prefix: first,
+                length: step - first,
+            });
+        }
+        let following = next(&node)?;
+        seen.insert(node, step);
+        node = following;
+    }
+
+    unreachable!()
+}
+
+/// Orders `nodes` and everything reachable from them so every edge points
+/// forward, or returns `None` if the edges contain a cycle.
+pub fn topological_sort<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    #[derive(PartialEq)]
+    enum Mark {
+        Open,
+        Done,
+    }
+
+    let mut marks: HashMap<N, Mark> = HashMap::new();
+    let mut order = Vec::new();
+
+    for root in nodes {
+        if marks.contains_key(&root) {
+            continue;
+        }
+        marks.insert(root.clone(), Mark::Open);
+        let mut stack = vec![(
+            root.clone(),
+            neighbours(&root).into_iter().collect::<Vec<_>>(),
+        )];
+
+        while let Some((node, pending)) = stack.last_mut() {
+            match pending.pop() {
+                Some(next) => match marks.get(&next) {
+                    Some(Mark::Open) => return None,
+                    Some(Mark::Done) => {}
+                    None => {
+                        marks.insert(next.clone(), Mark::Open);
+                        let out = neighbours(&next).into_iter().collect();
+                        stack.push((next, out));
+                    }
+                },
+                None => {
+                    marks.insert(node.clone(), Mark::Done);
+                    order.push(node.clone());
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    order.reverse();
+    Some(order)
+}
+
+/// Groups `nodes` into components; `neighbours` should list edges in both
+/// directions for the result to match the undirected components.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen: HashSet<N> = HashSet::new();
+    let mut components = Vec::new();
+
+    for root in nodes {
+        if !seen.insert(root.clone()) {
+            continue;
+        }
+        let mut component = Vec::new();
+        let mut pending = vec![root];
+        while let Some(node) = pending.pop() {
+            for next in neighbours(&node) {
+                if seen.insert(next.clone()) {
+                    pending.push(next);
+                }
+            }
+            component.push(node);
+        }
+        components.push(component);
+    }
+
+    components
+}
+
+/// Heaviest path from `start` to every reachable node, or `None` if a cycle
+/// is reachable or a path weight overflows.
+pub fn longest_paths<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Option<HashMap<N, u64>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut edges: HashMap<N, Vec<(N, u64)>> = HashMap::new();
+    let order = topological_sort([start.clone()], |node| {
+        let out: Vec<(N, u64)> = neighbours(node).into_iter().collect();
+        let successors: Vec<N> = out.iter().map(|(next, _)| next.clone()).collect();
+        edges.insert(node.clone(), out);
+        successors
+    })?;
+
+    let mut lengths = HashMap::from([(start, 0_u64)]);
+    for node in order {
+        let length = lengths[&node];
+        for (next, weight) in &edges[&node] {
+            let candidate = length.checked_add(*weight)?;
+            let best = lengths.entry(next.clone()).or_insert(candidate);
+            *best = (*best).max(candidate);
+        }
+    }
+
+    Some(lengths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_neighbours(&(x, y): &(i32, i32)) -> Vec<((i32, i32), u64)> {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y) && !(x == 2 && y < 4))
+            .map(|node| (node, 1))
+            .collect()
+    }
+
+    #[test]
+    fn shortest_paths_agree() {
+        let unweighted = |node: &(i32, i32)| grid_neighbours(node).into_iter().map(|(n, _)| n);
+        assert_eq!(bfs((0, 0), unweighted)[&(4, 0)], 12);
+
+        let (cost, path) = dijkstra((0, 0), grid_neighbours, |&n| n == (4, 0)).unwrap();
+        assert_eq!((cost, path.len()), (12, 13));
+
+        let manhattan = |&(x, y): &(i32, i32)| ((4 - x).abs() + y.abs()) as u64;
+        let (cost, path) = astar((0, 0), grid_neighbours, manhattan, |&n| n == (4, 0)).unwrap();
+        assert_eq!((cost, path[0], path[12]), (12, (0, 0), (4, 0)));
+
+        assert!(dijkstra((0, 0), grid_neighbours, |&n| n == (9, 9)).is_none());
+    }
+
+    #[test]
+    fn graph_from_closure_finds_reachable_nodes() {
+        let graph = Graph::from_fn([(0, 0)], grid_neighbours);
+        assert_eq!(graph.nodes().count(), 21);
+        assert_eq!(graph.successors(&(0, 0)).count(), 2);
+    }
+
+    #[test]
+    fn cycles_and_orderings() {
+        assert_eq!(
+            find_cycle(0, |&n| Some(if n < 7 { n + 1 } else { 3 })),
+            Some(Cycle {
+                prefix: 3,
+                length: 5
+            })
+        );
+        assert_eq!(find_cycle(0, |&n| (n < 3).then_some(n + 1)), None);
+
+        let dag = Graph::from_edges([('a', 'b', 1), ('a', 'c', 5), ('b', 'c', 1), ('c', 'd', 2)]);
+        let order = topological_sort(['d', 'c', 'b', 'a'], |n| dag.successors(n)).unwrap();
+        assert_eq!(order, vec!['a', 'b', 'c', 'd']);
+        assert_eq!(longest_paths('a', |n| dag.edges(n)).unwrap()[&'d'], 7);
+
+        let cyclic = Graph::from_edges([(1, 2, 1), (2, 3, 1), (3, 1, 1)]);
+        assert!(topological_sort([1], |n| cyclic.successors(n)).is_none());
+        assert!(longest_paths(1, |n| cyclic.edges(n)).is_none());
+    }
+
+    #[test]
+    fn components_are_disjoint() {
+        let graph = Graph::from_edges([(1, 2, 1), (2, 1, 1), (3, 4, 1), (4, 3, 1), (5, 5, 1)]);
+        let mut sizes: Vec<usize> = connected_components([1, 2, 3, 4, 5], |n| graph.successors(n))
+            .iter()
+            .map(Vec::len)
+            .collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 2, 2]);
+    }
+}
@@ -0,0 +1,239 @@
+use std::{path::Path, process::Command, time::Duration};
+
+use crate::{
+    answer::Answer,
+    records::HISTORY_PATH,
+    tsv::{append_rows, read_rows},
+};
+
+/// Runs this much slower or faster than the previous run on the same input
+/// are called out as timing changes.
+const TIMING_CHANGE_RATIO: f64 = 1.25;
+
+/// One solver run, appended to the history file and never rewritten.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    /// Unix seconds.
+    pub timestamp: u64,
+    pub revision: String,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub elapsed: Duration,
+    pub input_hash: u64,
+}
+
+impl HistoryEntry {
+    fn parse(row: &[String]) -> Option<HistoryEntry> {
+        match row {
+            [timestamp, revision, day, part, answer, elapsed, input_hash] => Some(HistoryEntry {
+                timestamp: timestamp.parse().ok()?,
+                revision: revision.clone(),
+                day: day.parse().ok()?,
+                part: part.parse().ok()?,
+                answer: Answer::parse(answer),
+                elapsed: Duration::from_nanos(elapsed.parse().ok()?),
+                input_hash: u64::from_str_radix(input_hash, 16).ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_row(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.revision.clone(),
+            self.day.to_string(),
+            self.part.to_string(),
+            self.answer.to_string(),
+            self.elapsed.as_nanos().to_string(),
+            format!("{:016x}", self.input_hash),
+        ]
+    }
+}
+
+pub fn append(path: &str, entries: &[HistoryEntry]) -> Result<(), String> {
+    append_rows(Path::new(path), entries.iter().map(HistoryEntry::to_row))
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+pub fn load(path: &str) -> Result<Vec<HistoryEntry>, String> {
+    Ok(read_rows(Path::new(path))
+        .map_err(|e| format!("{}: {}", path, e))?
+        .iter()
+        .filter_map(|row| HistoryEntry::parse(row))
+        .collect())
+}
+
+/// Short hash of the checked out commit, with `-dirty` if the work tree has
+/// changes, or `unknown` outside a git repository.
+pub fn git_revision() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(revision) => match git(&["status", "--porcelain", "--untracked-files=no"]) {
+            Some(changes) if !changes.is_empty() => format!("{}-dirty", revision),
+            _ => revision,
+        },
+        None => "unknown".to_string(),
+    }
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC.
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let minutes = timestamp % 86400 / 60;
+
+    // civil-from-days, the inverse of leaderboard::days_from_civil
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// What differs from the previous run of the same part on the same input.
+fn changes(entry: &HistoryEntry, previous: Option<&HistoryEntry>) -> String {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return String::new(),
+    };
+
+    let mut notes = Vec::new();
+    if entry.answer != previous.answer {
+        notes.push(format!("answer changed from {}", previous.answer));
+    }
+
+    let ratio = entry.elapsed.as_secs_f64() / previous.elapsed.as_secs_f64().max(1e-9);
+    if ratio >= TIMING_CHANGE_RATIO {
+        notes.push(format!("{:.1}x slower", ratio));
+    } else if ratio <= 1.0 / TIMING_CHANGE_RATIO {
+        notes.push(format!("{:.1}x faster", 1.0 / ratio));
+    }
+
+    notes.join(", ")
+}
+
+/// The latest run before `entries[i]` of the same part on the same input.
+fn previous(entries: &[HistoryEntry], i: usize) -> Option<&HistoryEntry> {
+    let entry = &entries[i];
+    entries[..i]
+        .iter()
+        .rev()
+        .find(|e| e.part == entry.part && e.input_hash == entry.input_hash)
+}
+
+/// Lists the runs of a day, oldest first, noting where answers and timings
+/// changed relative to the previous run on the same input.
+pub fn print(day: u8, part: Option<u8>) -> Result<(), String> {
+    let entries: Vec<HistoryEntry> = load(HISTORY_PATH)?
+        .into_iter()
+        .filter(|e| e.day == day && part.is_none_or(|p| e.part == p))
+        .collect();
+    if entries.is_empty() {
+        println!("no runs recorded for day {}", day);
+        return Ok(());
+    }
+
+    println!(
+        "{:<16}  {:<14} {:>4}  {:<16} {:>12}  {:<8}  changes",
+        "when", "revision", "part", "answer", "time", "input"
+    );
+    for (i, entry) in entries.iter().enumerate() {
+        let line = format!(
+            "{:<16}  {:<14} {:>4}  {:<16} {:>12}  {:<8}  {}",
+            format_timestamp(entry.timestamp),
+            entry.revision,
+            entry.part,
+            entry.answer,
+            format!("{:.2?}", entry.elapsed),
+            format!("{:08x}", entry.input_hash >> 32),
+            changes(entry, previous(&entries, i))
+        );
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(part: u8, answer: u64, elapsed_ms: u64, input_hash: u64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: 1701406800,
+            revision: "abc1234-dirty".to_string(),
+            day: 5,
+            part,
+            answer: answer.into(),
+            elapsed: Duration::from_millis(elapsed_ms),
+            input_hash,
+        }
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let original = entry(2, 46, 3, u64::MAX - 1);
+        let row = original.to_row();
+        let parsed = HistoryEntry::parse(&row).unwrap();
+
+        assert_eq!(row[6], "fffffffffffffffe");
+        assert_eq!(parsed.to_row(), row);
+        assert_eq!(parsed.input_hash, original.input_hash);
+        assert!(HistoryEntry::parse(&row[1..]).is_none());
+        assert_eq!(format_timestamp(original.timestamp), "2023-12-01 05:00");
+    }
+
+    #[test]
+    fn notes_answer_and_timing_changes() {
+        let base = entry(1, 35, 100, 7);
+
+        assert_eq!(changes(&base, None), "");
+        assert_eq!(changes(&entry(1, 35, 110, 7), Some(&base)), "");
+        assert_eq!(
+            changes(&entry(1, 36, 300, 7), Some(&base)),
+            "answer changed from 35, 3.0x slower"
+        );
+        assert_eq!(changes(&entry(1, 35, 50, 7), Some(&base)), "2.0x faster");
+    }
+
+    #[test]
+    fn compares_with_the_same_part_and_input() {
+        let entries = [
+            entry(1, 35, 100, 7),
+            entry(2, 46, 100, 7),
+            entry(1, 12, 100, 8),
+            entry(1, 35, 100, 7),
+        ];
+
+        assert!(previous(&entries, 0).is_none());
+        assert!(previous(&entries, 2).is_none());
+        assert_eq!(previous(&entries, 3).unwrap().answer, Answer::from(35_u64));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use crate::{
+    error::Result,
+    q03, q05, q07, q08, q10, q11, q12,
+    read_input::{hash_input, Input},
+};
+
+/// A fact about an input, printed as `name: value`.
+pub type Stat = (&'static str, String);
+
+/// Day specific facts about a parsed input.
+pub type Inspector = fn(Input) -> Result<Vec<Stat>>;
+
+pub fn inspector(day: u8) -> Option<Inspector> {
+    match day {
+        3 => Some(q03::inspect),
+        5 => Some(q05::inspect),
+        7 => Some(q07::inspect),
+        8 => Some(q08::inspect),
+        10 => Some(q10::inspect),
+        11 => Some(q11::inspect),
+        12 => Some(q12::inspect),
+        _ => None,
+    }
+}
+
+/// Facts that hold for any input: size, line counts and widths, the
+/// characters used and the hash answers are cached under.
+pub fn general(input: &[u8]) -> Vec<Stat> {
+    let text = String::from_utf8_lossy(input);
+    let lines: Vec<&str> = text.lines().collect();
+    let widths = lines.iter().map(|line| line.chars().count());
+    let mut characters: Vec<char> = text
+        .chars()
+        .filter(|c| *c != '\n')
+        .collect::<HashSet<char>>()
+        .into_iter()
+        .collect();
+    characters.sort_unstable();
+
+    vec![
+        ("bytes", input.len().to_string()),
+        ("lines", lines.len().to_string()),
+        (
+            "blank lines",
+            lines
+                .iter()
+                .filter(|line| line.trim().is_empty())
+                .count()
+                .to_string(),
+        ),
+        (
+            "width",
+            format!(
+                "{}..={}",
+                widths.clone().min().unwrap_or(0),
+                widths.max().unwrap_or(0)
+            ),
+        ),
+        (
+            "characters",
+            characters
+                .into_iter()
+                .collect::<String>()
+                .escape_debug()
+                .to_string(),
+        ),
+        ("hash", format!("{:016x}", hash_input(input))),
+    ]
+}
+
+/// `value (count)` pairs, in the order given.
+pub fn histogram<T: ToString>(counts: impl IntoIterator<Item = (T, usize)>) -> String {
+    counts
+        .into_iter()
+        .map(|(value, count)| format!("{} ({})", value.to_string(), count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn to_text(stats: &[Stat]) -> String {
+    stats
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_input::input_from_bytes;
+
+    fn inspect(day: u8, example: &str) -> String {
+        let inspector = inspector(day).unwrap();
+        to_text(&inspector(input_from_bytes(example.as_bytes().to_vec())).unwrap())
+    }
+
+    #[test]
+    fn general_counts_lines_and_widths() {
+        let stats = general(b"ab\n\nabcd\n");
+        let value = |name| stats.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+
+        assert_eq!(value("lines"), "3");
+        assert_eq!(value("blank lines"), "1");
+        assert_eq!(value("width"), "0..=4");
+        assert_eq!(value("characters"), "abcd");
+    }
+
+    #[test]
+    fn histogram_keeps_order() {
+        assert_eq!(histogram([("b", 2), ("a", 0)]), "b (2), a (0)");
+    }
+
+    #[test]
+    fn day03() {
+        assert_eq!(
+            inspect(3, include_str!("../tests/fixtures/day03.txt")),
+            "part numbers: 10\ngears: 3\ngears next to two numbers: 2"
+        );
+    }
+
+    #[test]
+    fn day05() {
+        assert_eq!(
+            inspect(5, include_str!("../tests/fixtures/day05.txt")),
+            "seeds: 4\nmaps: 7\nmappings per map: 2, 3, 4, 2, 3, 2, 2"
+        );
+    }
+
+    #[test]
+    fn day07() {
+        assert_eq!(
+            inspect(7, include_str!("../tests/fixtures/day07.txt")),
+            "hands: 5\n\
+             types: FiveOfAKind (0), FourOfAKind (0), FullHouse (0), ThreeOfAKind (2), \
+             TwoPair (2), OnePair (1), HighCard (0)\n\
+             types with jokers: FiveOfAKind (0), FourOfAKind (3), FullHouse (0), \
+             ThreeOfAKind (0), TwoPair (1), OnePair (1), HighCard (0)"
+        );
+    }
+
+    #[test]
+    fn day08() {
+        assert_eq!(
+            inspect(8, include_str!("../tests/fixtures/day08.txt")),
+            "directions: 2\nnodes: 10\n..A starts: 3 (11A, 22A, AAA)\n..Z ends: 3"
+        );
+    }
+
+    #[test]
+    fn day10() {
+        assert_eq!(
+            inspect(10, include_str!("../tests/fixtures/day10.txt")),
+            "grid: 10 x 20\nstart: row 1 column 5\n\
+             loop length: 160 (farthest tile 80 steps away)"
+        );
+    }
+
+    #[test]
+    fn day11() {
+        assert_eq!(
+            inspect(11, include_str!("../tests/fixtures/day11.txt")),
+            "galaxies: 9\nempty rows: 2 of 10\nempty columns: 3 of 10"
+        );
+    }
+
+    #[test]
+    fn day12() {
+        assert_eq!(
+            inspect(12, include_str!("../tests/fixtures/day12.txt")),
+            "records: 6\nquestion marks: 33\n\
+             rows by question mark count: 3 (1), 4 (2), 5 (1), 8 (1), 9 (1)"
+        );
+    }
+}
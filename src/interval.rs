@@ -0,0 +1,350 @@
+use std::fmt;
+
+/// Integer types usable as interval endpoints.
+pub trait Endpoint: Copy + Ord + fmt::Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! endpoint {
+    ($($t:ty),*) => {$(
+        impl Endpoint for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(value: i128) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+        }
+    )*};
+}
+
+endpoint!(u32, u64, usize, i32, i64);
+
+/// A non-empty run of consecutive integers. Both ends are stored inclusively
+/// so intervals reaching `T::MAX` need no sentinel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    start: T,
+    last: T,
+}
+
+impl<T: Endpoint> Interval<T> {
+    /// `start..end`, or `None` if it is empty.
+    pub fn new(start: T, end: T) -> Option<Interval<T>> {
+        let last = T::from_i128(end.to_i128() - 1)?;
+        Interval::inclusive(start, last)
+    }
+
+    /// `start..=last`, or `None` if it is empty.
+    pub fn inclusive(start: T, last: T) -> Option<Interval<T>> {
+        (start <= last).then_some(Interval { start, last })
+    }
+
+    /// `len` values from `start`, or `None` if empty or past `T::MAX`.
+    pub fn with_len(start: T, len: u128) -> Option<Interval<T>> {
+        let last = start.to_i128().checked_add(i128::try_from(len).ok()? - 1)?;
+        Interval::inclusive(start, T::from_i128(last)?)
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn last(&self) -> T {
+        self.last
+    }
+
+    /// Exclusive end, or `None` when the interval reaches `T::MAX`.
+    pub fn end(&self) -> Option<T> {
+        T::from_i128(self.last.to_i128() + 1)
+    }
+
+    // Intervals are never empty, so there is no `is_empty` to pair with this.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u128 {
+        (self.last.to_i128() - self.start.to_i128()) as u128 + 1
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.last
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::inclusive(self.start.max(other.start), self.last.min(other.last))
+    }
+
+    /// The parts of `self` outside `other`: zero, one or two intervals.
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if self.intersection(other).is_none() {
+            return vec![*self];
+        }
+
+        let before = T::from_i128(other.start.to_i128() - 1)
+            .and_then(|last| Interval::inclusive(self.start, last));
+        let after = T::from_i128(other.last.to_i128() + 1)
+            .and_then(|start| Interval::inclusive(start, self.last));
+        before.into_iter().chain(after).collect()
+    }
+
+    /// Cuts the interval so that every breakpoint inside it starts a new piece.
+    pub fn split(&self, breakpoints: &[T]) -> Vec<Interval<T>> {
+        let mut cuts: Vec<T> = breakpoints
+            .iter()
+            .copied()
+            .filter(|&b| self.start < b && b <= self.last)
+            .collect();
+        cuts.sort();
+        cuts.dedup();
+
+        let mut pieces = Vec::with_capacity(cuts.len() + 1);
+        let mut start = self.start;
+        for cut in cuts {
+            pieces.extend(Interval::new(start, cut));
+            start = cut;
+        }
+        pieces.extend(Interval::inclusive(start, self.last));
+        pieces
+    }
+
+    /// Moves both ends by `offset`, or `None` if either leaves `T`'s range.
+    pub fn shift(&self, offset: i128) -> Option<Interval<T>> {
+        let move_by = |value: T| T::from_i128(value.to_i128().checked_add(offset)?);
+        Some(Interval {
+            start: move_by(self.start)?,
+            last: move_by(self.last)?,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        let last = self.last;
+        std::iter::successors(Some(self.start), move |&value| {
+            if value == last {
+                None
+            } else {
+                T::from_i128(value.to_i128() + 1)
+            }
+        })
+    }
+}
+
+impl<T: Endpoint + fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.last)
+    }
+}
+
+/// A set of integers kept as sorted, disjoint, non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Endpoint> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet::new()
+    }
+}
+
+impl<T: Endpoint> FromIterator<Interval<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval<T>> = iter.into_iter().collect();
+        intervals.sort();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(previous) if interval.start.to_i128() <= previous.last.to_i128() + 1 => {
+                    previous.last = previous.last.max(interval.last);
+                }
+                _ => merged.push(interval),
+            }
+        }
+
+        RangeSet { intervals: merged }
+    }
+}
+
+impl<T: Endpoint> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(Interval::start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(Interval::last)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.last < value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        *self = self.intervals.iter().copied().chain([interval]).collect();
+    }
+
+    pub fn intervals(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.intervals.iter().flat_map(Interval::iter)
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            result.extend(a.intersection(b));
+            if a.last < b.last {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut remaining = self.intervals.clone();
+        for cut in &other.intervals {
+            remaining = remaining
+                .iter()
+                .flat_map(|interval| interval.difference(cut))
+                .collect();
+        }
+
+        RangeSet {
+            intervals: remaining,
+        }
+    }
+
+    /// Cuts every interval at the breakpoints, keeping the pieces separate.
+    pub fn split(&self, breakpoints: &[T]) -> Vec<Interval<T>> {
+        self.intervals
+            .iter()
+            .flat_map(|interval| interval.split(breakpoints))
+            .collect()
+    }
+
+    /// Moves the whole set by `offset`, or `None` if any value leaves `T`'s range.
+    pub fn shift(&self, offset: i128) -> Option<RangeSet<T>> {
+        let intervals = self
+            .intervals
+            .iter()
+            .map(|interval| interval.shift(offset))
+            .collect::<Option<Vec<_>>>()?;
+        Some(RangeSet { intervals })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i64, i64)]) -> RangeSet<i64> {
+        ranges
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn bounds_reach_the_type_limits() {
+        assert_eq!(Interval::new(5_u64, 5), None);
+        let top = Interval::inclusive(u64::MAX - 1, u64::MAX).unwrap();
+        assert_eq!((top.len(), top.end()), (2, None));
+        assert_eq!(top.iter().count(), 2);
+        assert_eq!(top.shift(1), None);
+        assert_eq!(
+            Interval::with_len(u64::MAX, 1),
+            Some(Interval::inclusive(u64::MAX, u64::MAX).unwrap())
+        );
+        assert_eq!(Interval::with_len(u64::MAX, 2), None);
+        let all = Interval::inclusive(i64::MIN, i64::MAX).unwrap();
+        assert_eq!(all.len(), 1 << 64);
+        assert_eq!(
+            all.difference(&Interval::inclusive(i64::MIN, 0).unwrap()),
+            vec![Interval::inclusive(1, i64::MAX).unwrap()]
+        );
+    }
+
+    #[test]
+    fn interval_operations() {
+        let a = Interval::new(0_i64, 10).unwrap();
+        let b = Interval::new(5, 15).unwrap();
+        assert_eq!(a.intersection(&b), Interval::new(5, 10));
+        assert_eq!(
+            a.difference(&Interval::new(3, 6).unwrap()),
+            vec![Interval::new(0, 3).unwrap(), Interval::new(6, 10).unwrap()]
+        );
+        assert_eq!(
+            a.split(&[7, 3, 3, 20, 0]),
+            vec![
+                Interval::new(0, 3).unwrap(),
+                Interval::new(3, 7).unwrap(),
+                Interval::new(7, 10).unwrap()
+            ]
+        );
+        assert_eq!(a.shift(-20), Interval::new(-20, -10));
+        assert_eq!(
+            Interval::new(1, 4).unwrap().iter().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn sets_merge_and_combine() {
+        let a = set(&[(0, 5), (5, 8), (20, 30)]);
+        assert_eq!(a, set(&[(0, 8), (20, 30)]));
+        assert_eq!(a.len(), 18);
+        assert!(a.contains(25) && !a.contains(8));
+
+        let b = set(&[(4, 22), (29, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(4, 8), (20, 22), (29, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (22, 29)]));
+
+        let mut c = RangeSet::new();
+        c.insert(Interval::new(10, 12).unwrap());
+        c.insert(Interval::new(12, 14).unwrap());
+        assert_eq!((c.min(), c.max()), (Some(10), Some(13)));
+        assert_eq!(
+            c.shift(5).unwrap().iter().collect::<Vec<_>>(),
+            vec![15, 16, 17, 18]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DayCompletion {
+    /// Unix timestamps at which each star was earned.
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+}
+
+impl DayCompletion {
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.part2? - self.part1?)
+    }
+}
+
+#[derive(Debug)]
+pub struct Member {
+    pub name: String,
+    pub stars: u64,
+    pub local_score: u64,
+    pub days: BTreeMap<u8, DayCompletion>,
+}
+
+#[derive(Debug)]
+pub struct Leaderboard {
+    pub event: i64,
+    pub members: Vec<Member>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SortBy {
+    Score,
+    Stars,
+    Name,
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::parse(1, message)
+}
+
+fn star_timestamp(part: Option<&Value>) -> Option<i64> {
+    part?.get("get_star_ts")?.as_i64()
+}
+
+impl Leaderboard {
+    /// Parses the JSON export of a private leaderboard.
+    pub fn parse(json: &str) -> Result<Leaderboard> {
+        let root: Value =
+            serde_json::from_str(json).map_err(|e| Error::parse(e.line(), e.to_string()))?;
+
+        let event = match &root["event"] {
+            Value::String(event) => event
+                .parse()
+                .map_err(|_| invalid(format!("invalid event {}", event)))?,
+            Value::Number(event) => event.as_i64().ok_or_else(|| invalid("invalid event"))?,
+            _ => return Err(invalid("missing event")),
+        };
+
+        let members = root["members"]
+            .as_object()
+            .ok_or_else(|| invalid("missing members"))?;
+
+        let mut parsed = Vec::new();
+        for (id, member) in members {
+            let name = match member["name"].as_str() {
+                Some(name) => name.to_string(),
+                None => format!("(anonymous user #{})", id),
+            };
+
+            let mut days = BTreeMap::new();
+            if let Some(completed) = member["completion_day_level"].as_object() {
+                for (day, parts) in completed {
+                    let day = day
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid day {}", day)))?;
+                    days.insert(
+                        day,
+                        DayCompletion {
+                            part1: star_timestamp(parts.get("1")),
+                            part2: star_timestamp(parts.get("2")),
+                        },
+                    );
+                }
+            }
+
+            parsed.push(Member {
+                name,
+                stars: member["stars"].as_u64().unwrap_or(0),
+                local_score: member["local_score"].as_u64().unwrap_or(0),
+                days,
+            });
+        }
+
+        Ok(Leaderboard {
+            event,
+            members: parsed,
+        })
+    }
+
+    /// Unix timestamp at which the puzzle for `day` unlocked (midnight EST).
+    pub fn unlock_time(&self, day: u8) -> i64 {
+        days_from_civil(self.event, 12, day as i64) * 86400 + 5 * 3600
+    }
+
+    pub fn sort(&mut self, by: SortBy) {
+        match by {
+            SortBy::Score => self.members.sort_by(|a, b| {
+                b.local_score
+                    .cmp(&a.local_score)
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+            SortBy::Stars => self
+                .members
+                .sort_by(|a, b| b.stars.cmp(&a.stars).then_with(|| a.name.cmp(&b.name))),
+            SortBy::Name => self.members.sort_by(|a, b| a.name.cmp(&b.name)),
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{:<30} {:>5} {:>6}", "Member", "Stars", "Score");
+        for member in &self.members {
+            println!(
+                "{:<30} {:>5} {:>6}",
+                member.name, member.stars, member.local_score
+            );
+            for (day, completion) in &member.days {
+                self.print_completion(*day, completion);
+            }
+        }
+    }
+
+    /// Everyone who finished part 1 of `day`, fastest part 2 first.
+    pub fn finishers(&self, day: u8) -> Vec<(&Member, &DayCompletion)> {
+        let mut finished: Vec<(&Member, &DayCompletion)> = self
+            .members
+            .iter()
+            .filter_map(|m| m.days.get(&day).map(|c| (m, c)))
+            .filter(|(_, c)| c.part1.is_some())
+            .collect();
+        finished.sort_by_key(|(_, c)| (c.part2.unwrap_or(i64::MAX), c.part1));
+        finished
+    }
+
+    pub fn print_day(&self, day: u8) {
+        println!("Day {}", day);
+        for (member, completion) in self.finishers(day) {
+            print!("{:<30}", member.name);
+            self.print_completion(day, completion);
+        }
+    }
+
+    fn print_completion(&self, day: u8, completion: &DayCompletion) {
+        let unlock = self.unlock_time(day);
+        let since_unlock = |ts: Option<i64>| ts.map(|ts| format_duration(ts - unlock));
+        println!(
+            "  day {:>2}  part 1 {:>10}  part 2 {:>10}  delta {:>10}",
+            day,
+            since_unlock(completion.part1).unwrap_or_else(|| "-".to_string()),
+            since_unlock(completion.part2).unwrap_or_else(|| "-".to_string()),
+            completion
+                .delta()
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+}
+
+pub fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    format!(
+        "{}{:02}:{:02}:{:02}",
+        sign,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Leaderboard {
+        Leaderboard::parse(include_str!("../tests/fixtures/leaderboard.json")).unwrap()
+    }
+
+    fn names(leaderboard: &Leaderboard) -> Vec<&str> {
+        leaderboard
+            .members
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parses_members_and_stars() {
+        let leaderboard = fixture();
+        let ada = &leaderboard.members[0];
+
+        assert_eq!(leaderboard.event, 2023);
+        assert_eq!(
+            (ada.name.as_str(), ada.stars, ada.local_score),
+            ("Ada", 3, 10)
+        );
+        assert_eq!(ada.days[&2].part1, Some(1701496800));
+        assert_eq!(ada.days[&2].part2, None);
+        assert_eq!(leaderboard.members[2].name, "(anonymous user #3)");
+    }
+
+    #[test]
+    fn times_stars_from_unlock() {
+        let leaderboard = fixture();
+        let day1 = leaderboard.members[0].days[&1];
+
+        assert_eq!(leaderboard.unlock_time(1), 1701406800);
+        assert_eq!(
+            format_duration(day1.part1.unwrap() - leaderboard.unlock_time(1)),
+            "00:05:00"
+        );
+        assert_eq!(
+            day1.delta().map(format_duration).as_deref(),
+            Some("00:10:00")
+        );
+        assert_eq!(leaderboard.members[0].days[&2].delta(), None);
+    }
+
+    #[test]
+    fn sorts_with_ties_by_name() {
+        let mut leaderboard = fixture();
+
+        leaderboard.sort(SortBy::Score);
+        assert_eq!(names(&leaderboard), ["Ada", "Brian", "(anonymous user #3)"]);
+        leaderboard.sort(SortBy::Stars);
+        assert_eq!(names(&leaderboard), ["Brian", "Ada", "(anonymous user #3)"]);
+        leaderboard.sort(SortBy::Name);
+        assert_eq!(names(&leaderboard), ["(anonymous user #3)", "Ada", "Brian"]);
+    }
+
+    #[test]
+    fn filters_a_day_fastest_part_two_first() {
+        let leaderboard = fixture();
+        let finishers = |day| {
+            leaderboard
+                .finishers(day)
+                .iter()
+                .map(|(m, _)| m.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        // Ada finished part 2 of day 1 first; on day 2 only Brian has both stars
+        assert_eq!(finishers(1), ["Ada", "Brian"]);
+        assert_eq!(finishers(2), ["Brian", "Ada"]);
+        assert!(finishers(3).is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+pub mod answer;
+pub mod batch;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod checked;
+pub mod compare;
+pub mod crash;
+pub mod dump;
+pub mod error;
+pub mod fetch;
+pub mod graph;
+pub mod history;
+pub mod inspect;
+pub mod interval;
+pub mod leaderboard;
+pub mod math;
+pub mod memory;
+pub mod parse;
+pub mod puzzle;
+pub mod q03;
+pub mod q04;
+pub mod q05;
+pub mod q06;
+pub mod q07;
+pub mod q08;
+pub mod q09;
+pub mod q10;
+pub mod q11;
+pub mod q12;
+pub mod query;
+pub mod read_input;
+pub mod records;
+pub mod repl;
+pub mod runner;
+pub mod server;
+pub mod solutions;
+pub mod status;
+pub mod trace;
+pub mod tsv;
+pub mod validate;
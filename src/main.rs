@@ -1,19 +1,305 @@
-#![allow(dead_code)]
+mod args;
 
-mod q03;
-mod q04;
-mod q05;
-mod q06;
-mod q07;
-mod q08;
-mod q09;
-mod q10;
-mod q11;
-mod q12;
-mod read_input;
+use std::{fs, process::ExitCode, time::Duration};
 
-fn main() {
-    let lines = read_input::read_input_lines("input12.txt");
-    q12::part1(lines);
+use aoc::{
+    batch, bench, compare, dump, fetch, history, inspect,
+    leaderboard::{Leaderboard, SortBy},
+    memory, puzzle,
+    read_input::{input_from_bytes, read_input_bytes, read_input_lines},
+    records, repl, runner, server, solutions, status, validate,
+};
+use args::Args;
+
+#[global_allocator]
+static ALLOCATOR: memory::CountingAlloc = memory::CountingAlloc;
+
+const USAGE: &str = "usage:
+    aoc run --day N [--part P] [--input FILE] [--no-cache] [--refresh] [--accept] [--stream] [--memory]
+            [--trace | --trace-json FILE] [--timeout SECS]
+    aoc bench --day N [--part P] [--input FILE] [--iterations K] [--compare-input]
+    aoc bench [--day N] [--part P] [--input FILE] [--iterations K] [--timeout SECS]
+            (--save-baseline | --check-baseline [--max-regression PCT] [--noise SECS])
+    aoc batch --day N [--part P] --dir DIR [--timeout SECS]
+    aoc compare --day N [--part P] [--input FILE | --dir DIR] [--config FILE] [--timeout SECS]
+    aoc status [--json]
+    aoc history --day N [--part P]
+    aoc repl --day N [--input FILE]
+    aoc validate --day N [--input FILE]
+    aoc dump --day N [--input FILE]
+    aoc inspect --day N [--input FILE]
+    aoc serve [--addr HOST:PORT] [--max-body-bytes N] [--max-connections N] [--timeout SECS]
+    aoc leaderboard (--file FILE | --url URL [--session TOKEN]) [--day N] [--sort score|stars|name]
+    aoc show --day N [--import FILE | --fetch --base-url URL [--session TOKEN]]";
+
+/// Reads `--day`, `--part` and `--input`, defaulting the input to the day's file.
+fn day_part_input(args: &Args) -> Result<(u8, Option<u8>, String), String> {
+    let day: u8 = args.parse_value("day")?.ok_or("missing --day")?;
+    let part: Option<u8> = args.parse_value("part")?;
+    let input = args
+        .value("input")
+        .map(str::to_string)
+        .unwrap_or_else(|| solutions::default_input_path(day));
+
+    Ok((day, part, input))
+}
+
+/// Reads `--NAME` as a duration in (possibly fractional) seconds.
+fn seconds(args: &Args, name: &str) -> Result<Option<Duration>, String> {
+    match args.parse_value::<f64>(name)? {
+        Some(secs) => Duration::try_from_secs_f64(secs)
+            .map(Some)
+            .map_err(|_| format!("invalid value for --{}: {}", name, secs)),
+        None => Ok(None),
+    }
+}
+
+/// Reads `--timeout` as a per-part budget in seconds.
+fn timeout(args: &Args) -> Result<Option<Duration>, String> {
+    seconds(args, "timeout")
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let (day, part, input) = day_part_input(args)?;
+    let options = runner::RunOptions {
+        no_cache: args.flag("no-cache"),
+        refresh: args.flag("refresh"),
+        accept: args.flag("accept"),
+        stream: args.flag("stream"),
+        memory: args.flag("memory"),
+        trace: match args.value("trace-json") {
+            Some(path) => Some(runner::TraceOutput::JsonLines(path.to_string())),
+            None if args.flag("trace") => Some(runner::TraceOutput::Text),
+            None => None,
+        },
+        timeout: timeout(args)?,
+        records: records::RecordPaths::default(),
+    };
+
+    runner::run(day, part, &input, &options)
+}
+
+fn bench(args: &Args) -> Result<(), String> {
+    let iterations = args.parse_value("iterations")?.unwrap_or(10);
+
+    if args.flag("compare-input") {
+        let (day, part, input) = day_part_input(args)?;
+        return bench::compare_input(day, part, &input, iterations);
+    }
+
+    let mode = match (args.flag("save-baseline"), args.flag("check-baseline")) {
+        (true, true) => return Err("--save-baseline and --check-baseline conflict".to_string()),
+        (true, false) => bench::BaselineMode::Save,
+        (false, true) => {
+            let default = bench::Budget::default();
+            let max_regression = args
+                .parse_value("max-regression")?
+                .unwrap_or(default.max_regression);
+            if max_regression < 0.0 || max_regression.is_nan() {
+                return Err(format!(
+                    "invalid value for --max-regression: {}",
+                    max_regression
+                ));
+            }
+            bench::BaselineMode::Check(bench::Budget {
+                max_regression,
+                noise: seconds(args, "noise")?.unwrap_or(default.noise),
+            })
+        }
+        (false, false) => bench::BaselineMode::Ignore,
+    };
+    let day: Option<u8> = args.parse_value("day")?;
+    if day.is_none() && matches!(mode, bench::BaselineMode::Ignore) {
+        return Err("missing --day".to_string());
+    }
+
+    bench::bench(
+        day,
+        args.parse_value("part")?,
+        args.value("input"),
+        iterations,
+        timeout(args)?,
+        mode,
+    )
+}
+
+fn batch(args: &Args) -> Result<(), String> {
+    let day: u8 = args.parse_value("day")?.ok_or("missing --day")?;
+    let part: Option<u8> = args.parse_value("part")?;
+    let dir = args.value("dir").ok_or("missing --dir")?;
+
+    batch::batch(day, part, dir, timeout(args)?)
+}
+
+fn compare(args: &Args) -> Result<(), String> {
+    let (day, part, input) = day_part_input(args)?;
+    let inputs = compare::inputs(args.value("dir"), &input)?;
+    let config = args.value("config").unwrap_or(records::COMPARE_PATH);
+
+    compare::compare(day, part, &inputs, config, timeout(args)?)
+}
+
+fn status(args: &Args) -> Result<(), String> {
+    let days = status::collect()?;
+    if args.flag("json") {
+        println!("{:#}", status::to_json(&days));
+    } else {
+        status::print_calendar(&days);
+    }
+
+    Ok(())
+}
+
+fn history(args: &Args) -> Result<(), String> {
+    let day: u8 = args.parse_value("day")?.ok_or("missing --day")?;
+    let part: Option<u8> = args.parse_value("part")?;
+
+    history::print(day, part)
+}
+
+fn repl(args: &Args) -> Result<(), String> {
+    let (day, _, input) = day_part_input(args)?;
+    repl::run(day, &input)
+}
+
+fn validate(args: &Args) -> Result<(), String> {
+    let (day, _, input) = day_part_input(args)?;
+    let validator =
+        validate::validator(day).ok_or_else(|| format!("no validator for day {}", day))?;
+    let lines = read_input_lines(&input).map_err(|e| format!("{}: {}", input, e))?;
+
+    let violations = validator(lines).map_err(|e| e.to_string())?;
+    for violation in &violations {
+        println!("{}", violation);
+    }
+
+    match violations.len() {
+        0 => {
+            println!("{}: ok", input);
+            Ok(())
+        }
+        n => Err(format!("{}: {} violation(s)", input, n)),
+    }
 }
 
+fn dump(args: &Args) -> Result<(), String> {
+    let (day, _, input) = day_part_input(args)?;
+    let dumper = dump::dumper(day).ok_or_else(|| format!("no dump for day {}", day))?;
+    let lines = read_input_lines(&input).map_err(|e| format!("{}: {}", input, e))?;
+
+    let model = dumper(lines).map_err(|e| format!("{}: {}", input, e))?;
+    println!("{:#}", model);
+
+    Ok(())
+}
+
+fn inspect(args: &Args) -> Result<(), String> {
+    let (day, _, input) = day_part_input(args)?;
+    let bytes = read_input_bytes(&input).map_err(|e| format!("{}: {}", input, e))?;
+
+    // the general facts still help when the day's parser rejects the input
+    println!("{}", inspect::to_text(&inspect::general(&bytes)));
+    if let Some(inspector) = inspect::inspector(day) {
+        let stats = inspector(input_from_bytes(bytes)).map_err(|e| format!("{}: {}", input, e))?;
+        println!("{}", inspect::to_text(&stats));
+    }
+
+    Ok(())
+}
+
+fn serve(args: &Args) -> Result<(), String> {
+    let addr = args.value("addr").unwrap_or("127.0.0.1:8080");
+    let options = server::ServerOptions {
+        max_body_bytes: args.parse_value("max-body-bytes")?.unwrap_or(1024 * 1024),
+        timeout: timeout(args)?.unwrap_or(Duration::from_secs(30)),
+        max_connections: args.parse_value("max-connections")?.unwrap_or(16),
+    };
+
+    server::serve(addr, options).map_err(|e| format!("{}: {}", addr, e))
+}
+
+fn leaderboard(args: &Args) -> Result<(), String> {
+    let json = match (args.value("file"), args.value("url")) {
+        (Some(path), _) => fs::read(path).map_err(|e| format!("{}: {}", path, e))?,
+        (None, Some(url)) => {
+            fetch::http_get(url, args.value("session")).map_err(|e| e.to_string())?
+        }
+        (None, None) => return Err("missing --file or --url".to_string()),
+    };
+    let mut leaderboard =
+        Leaderboard::parse(&String::from_utf8_lossy(&json)).map_err(|e| e.to_string())?;
+
+    let sort = match args.value("sort").unwrap_or("score") {
+        "score" => SortBy::Score,
+        "stars" => SortBy::Stars,
+        "name" => SortBy::Name,
+        other => return Err(format!("invalid value for --sort: {}", other)),
+    };
+    leaderboard.sort(sort);
+
+    match args.parse_value::<u8>("day")? {
+        Some(day) => leaderboard.print_day(day),
+        None => leaderboard.print(),
+    }
+
+    Ok(())
+}
+
+fn show(args: &Args) -> Result<(), String> {
+    let day: u8 = args.parse_value("day")?.ok_or("missing --day")?;
+
+    let html = if let Some(path) = args.value("import") {
+        Some(fs::read(path).map_err(|e| format!("{}: {}", path, e))?)
+    } else if args.flag("fetch") {
+        // only plain http is supported, so there is no usable default
+        let base_url = args
+            .value("base-url")
+            .ok_or("--fetch needs --base-url with an http:// mirror of the puzzle pages")?;
+        Some(puzzle::fetch(base_url, day, args.value("session")).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    if let Some(html) = html {
+        puzzle::store(day, &html).map_err(|e| format!("{}: {}", puzzle::PUZZLE_DIR, e))?;
+    }
+
+    let html = puzzle::load(day).map_err(|e| {
+        format!(
+            "{}: {} (import the page with --import or --fetch first)",
+            puzzle::puzzle_path(day).display(),
+            e
+        )
+    })?;
+    puzzle::render(&puzzle::html_to_markdown(&html));
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse(std::env::args().skip(1));
+
+    let result = match args.command.as_deref() {
+        Some("run") => run(&args),
+        Some("bench") => bench(&args),
+        Some("batch") => batch(&args),
+        Some("compare") => compare(&args),
+        Some("status") => status(&args),
+        Some("history") => history(&args),
+        Some("repl") => repl(&args),
+        Some("validate") => validate(&args),
+        Some("dump") => dump(&args),
+        Some("inspect") => inspect(&args),
+        Some("serve") => serve(&args),
+        Some("leaderboard") => leaderboard(&args),
+        Some("show") => show(&args),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
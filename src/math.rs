@@ -0,0 +1,210 @@
+use num::{rational::Ratio, CheckedMul, Integer};
+
+/// Greatest common divisor of all values; 0 for an empty slice.
+pub fn gcd_all<T: Integer + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |a, b| a.gcd(b))
+}
+
+/// Least common multiple of all values, or `None` on overflow; 1 for an empty slice.
+pub fn lcm_all<T: Integer + CheckedMul + Copy>(values: &[T]) -> Option<T> {
+    values.iter().try_fold(T::one(), |a, &b| {
+        if a.is_zero() || b.is_zero() {
+            return Some(T::zero());
+        }
+        (a / a.gcd(&b)).checked_mul(&b)
+    })
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return if a < 0 { (-a, -1, 0) } else { (a, 1, 0) };
+    }
+
+    let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+    (g, y, x - a.div_euclid(b) * y)
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair, allowing moduli that
+/// share factors. Returns `(x, lcm of the moduli)` with `0 <= x < lcm`, or
+/// `None` if the congruences contradict each other or the lcm overflows.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+
+    for &(residue, modulus) in congruences {
+        let (residue, modulus) = (residue as i128, modulus as i128);
+        if modulus <= 0 {
+            return None;
+        }
+
+        let (g, p, _) = extended_gcd(m, modulus);
+        let diff = residue - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (diff / g % step * (p % step)).rem_euclid(step);
+        x += m * k;
+        m = m.checked_mul(step)?;
+        if m > i64::MAX as i128 {
+            return None;
+        }
+        x = x.rem_euclid(m);
+    }
+
+    Some((x as i64, m as i64))
+}
+
+/// Largest `r` with `r * r <= n`, computed without floating point.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+
+    x
+}
+
+/// Evaluates `coefficients[0] + coefficients[1] * x + ...`, or `None` on overflow.
+pub fn evaluate(coefficients: &[i64], x: i64) -> Option<i64> {
+    coefficients
+        .iter()
+        .rev()
+        .try_fold(0_i64, |acc, &c| acc.checked_mul(x)?.checked_add(c))
+}
+
+/// Value at `x` of the lowest degree polynomial through `points`, or `None`
+/// if two points share an x, the value is not an integer, or it overflows.
+pub fn interpolate_at(points: &[(i64, i64)], x: i64) -> Option<i64> {
+    let mut total = Ratio::from_integer(0_i128);
+
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = Ratio::from_integer(yi as i128);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if xi == xj {
+                return None;
+            }
+            let factor = Ratio::new(x as i128 - xj as i128, xi as i128 - xj as i128);
+            term = term.checked_mul(&factor)?;
+        }
+        total = num::CheckedAdd::checked_add(&total, &term)?;
+    }
+
+    if !total.is_integer() {
+        return None;
+    }
+    i64::try_from(total.to_integer()).ok()
+}
+
+/// `base.pow(exp) % modulus` without overflow.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result: u128 = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm_over_slices() {
+        assert_eq!(gcd_all(&[12_u64, 18, 30]), 6);
+        assert_eq!(gcd_all::<u64>(&[]), 0);
+        assert_eq!(lcm_all(&[4_u128, 6, 10]), Some(60));
+        assert_eq!(lcm_all::<u64>(&[]), Some(1));
+        assert_eq!(lcm_all(&[u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn extended_gcd_finds_bezout_coefficients() {
+        for (a, b) in [(240, 46), (46, 240), (-15, 35), (7, 0), (0, 9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, num::integer::gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn crt_handles_coprime_and_shared_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn isqrt_is_exact() {
+        for n in 0..10_000_u128 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n);
+        }
+        assert_eq!(isqrt(u64::MAX as u128), u32::MAX as u128);
+        assert_eq!(isqrt((1 << 100) - 1), (1 << 50) - 1);
+    }
+
+    #[test]
+    fn evaluate_uses_ascending_coefficients() {
+        assert_eq!(evaluate(&[1, -2, 3], 2), Some(9));
+        assert_eq!(evaluate(&[], 5), Some(0));
+        assert_eq!(evaluate(&[0, 0, 1], i64::MAX), None);
+    }
+
+    #[test]
+    fn interpolate_extends_sequences() {
+        let points = |values: &[i64]| -> Vec<(i64, i64)> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| (i as i64, v))
+                .collect()
+        };
+        assert_eq!(interpolate_at(&points(&[0, 3, 6, 9, 12, 15]), 6), Some(18));
+        assert_eq!(
+            interpolate_at(&points(&[10, 13, 16, 21, 30, 45]), 6),
+            Some(68)
+        );
+        assert_eq!(
+            interpolate_at(&points(&[10, 13, 16, 21, 30, 45]), -1),
+            Some(5)
+        );
+        assert_eq!(interpolate_at(&[(0, 0), (2, 1)], 1), None);
+        assert_eq!(interpolate_at(&[(1, 0), (1, 1)], 2), None);
+    }
+
+    #[test]
+    fn mod_pow_does_not_overflow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(
+            mod_pow(u64::MAX, u64::MAX, u64::MAX - 58),
+            mod_pow(58, u64::MAX, u64::MAX - 58)
+        );
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_pow(5, 0, 7), 1);
+    }
+}
@@ -0,0 +1,277 @@
+use std::{io, str::FromStr};
+
+use crate::{
+    error::{Error, ParseErrorKind, Result},
+    read_input::Input,
+};
+
+fn error(line: usize, kind: ParseErrorKind) -> Error {
+    Error::Parse { line, kind }
+}
+
+/// Lines separated by blank lines, e.g. the sections of an almanac.
+#[derive(Debug, Clone)]
+pub struct Block {
+    /// 1-based line number of `lines[0]`.
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+pub fn read_lines(lines: Input) -> Result<Vec<String>> {
+    Ok(lines.collect::<std::io::Result<_>>()?)
+}
+
+pub fn blocks(lines: Input) -> Result<Vec<Block>> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut in_block = false;
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            in_block = false;
+            continue;
+        }
+
+        if in_block {
+            blocks.last_mut().unwrap().lines.push(line);
+        } else {
+            in_block = true;
+            blocks.push(Block {
+                first_line: i + 1,
+                lines: vec![line],
+            });
+        }
+    }
+
+    Ok(blocks)
+}
+
+pub fn number<T: FromStr>(token: &str, line: usize) -> Result<T> {
+    token
+        .parse()
+        .map_err(|_| error(line, ParseErrorKind::InvalidNumber(token.to_string())))
+}
+
+/// Parses every whitespace separated token of `text` as a number.
+pub fn numbers<T: FromStr>(text: &str, line: usize) -> Result<Vec<T>> {
+    text.split_whitespace()
+        .map(|token| number(token, line))
+        .collect()
+}
+
+/// Picks out every run of digits (with its sign, for signed `T`) that parses
+/// as `T`, skipping labels and punctuation around them.
+pub fn extract_numbers<T: FromStr>(text: &str) -> Vec<T> {
+    let mut numbers = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let end = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(rest.len(), |len| start + len);
+        let signed = if rest[..start].ends_with('-') {
+            start - 1
+        } else {
+            start
+        };
+        // unsigned types reject the sign, so fall back to the bare digits
+        if let Some(number) = rest[signed..end]
+            .parse()
+            .ok()
+            .or_else(|| rest[start..end].parse().ok())
+        {
+            numbers.push(number);
+        }
+        rest = &rest[end..];
+    }
+
+    numbers
+}
+
+/// Parses a list such as `1,1,3`.
+pub fn delimited<T: FromStr>(text: &str, delimiter: char, line: usize) -> Result<Vec<T>> {
+    text.split(delimiter)
+        .map(|token| number(token.trim(), line))
+        .collect()
+}
+
+/// Splits at the first `separator`.
+pub fn pair(text: &str, separator: char, line: usize) -> Result<(&str, &str)> {
+    text.split_once(separator)
+        .ok_or_else(|| error(line, ParseErrorKind::MissingSeparator(separator)))
+}
+
+/// Splits a `key: values` line, trimming both halves.
+pub fn key_values(text: &str, line: usize) -> Result<(&str, &str)> {
+    let (key, values) = pair(text, ':', line)?;
+    Ok((key.trim(), values.trim()))
+}
+
+/// Splits on any of `delimiters`, dropping empty tokens.
+pub fn tokens<'a>(text: &'a str, delimiters: &[char]) -> Vec<&'a str> {
+    text.split(delimiters).filter(|x| !x.is_empty()).collect()
+}
+
+/// Reads a rectangular grid, converting each character with `cell`.
+pub fn grid<T>(lines: Input, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    grid_rows(lines, cell, |row| {
+        rows.push(row);
+        Ok(())
+    })?;
+
+    Ok(rows)
+}
+
+/// Like `grid`, but hands each row to `visit` instead of keeping it, so only
+/// one row is in memory at a time. Returns the row width, `None` if there
+/// were no rows.
+pub fn grid_rows<T>(
+    lines: Input,
+    cell: impl Fn(char) -> Option<T>,
+    visit: impl FnMut(Vec<T>) -> Result<()>,
+) -> Result<Option<usize>> {
+    grid_lines(lines, cell, visit)
+}
+
+/// `grid` over text already in memory.
+pub fn grid_text<T>(text: &str, cell: impl Fn(char) -> Option<T>) -> Result<Vec<Vec<T>>> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    grid_lines(text.lines().map(Ok), cell, |row| {
+        rows.push(row);
+        Ok(())
+    })?;
+
+    Ok(rows)
+}
+
+fn grid_lines<T, S: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    cell: impl Fn(char) -> Option<T>,
+    mut visit: impl FnMut(Vec<T>) -> Result<()>,
+) -> Result<Option<usize>> {
+    let mut width = None;
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let row = line
+            .as_ref()
+            .chars()
+            .map(|c| cell(c).ok_or_else(|| error(i + 1, ParseErrorKind::UnexpectedChar(c))))
+            .collect::<Result<Vec<T>>>()?;
+
+        match width {
+            Some(expected) if expected != row.len() => {
+                return Err(error(
+                    i + 1,
+                    ParseErrorKind::RaggedRow {
+                        expected,
+                        found: row.len(),
+                    },
+                ));
+            }
+            _ => width = Some(row.len()),
+        }
+        visit(row)?;
+    }
+
+    Ok(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_input::input_from_bytes;
+
+    fn input(text: &str) -> Input {
+        input_from_bytes(text.as_bytes().to_vec())
+    }
+
+    fn parse_error<T: std::fmt::Debug>(result: Result<T>) -> (usize, ParseErrorKind) {
+        match result {
+            Err(Error::Parse { line, kind }) => (line, kind),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn splits_blocks_on_blank_lines() {
+        let blocks = blocks(input("seeds: 1 2\n\n\na\nb\n\nc\n")).unwrap();
+        let shapes: Vec<(usize, usize)> = blocks
+            .iter()
+            .map(|b| (b.first_line, b.lines.len()))
+            .collect();
+
+        assert_eq!(shapes, [(1, 1), (4, 2), (7, 1)]);
+        assert_eq!(blocks[1].lines, ["a", "b"]);
+        assert!(super::blocks(input("\n\n")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_numbers_with_line_numbers_in_errors() {
+        assert_eq!(numbers::<u32>(" 7  15 30 ", 1).unwrap(), [7, 15, 30]);
+        assert_eq!(numbers::<i64>("0 -3 6", 1).unwrap(), [0, -3, 6]);
+        assert_eq!(
+            parse_error(numbers::<u32>("1 -2", 4)),
+            (4, ParseErrorKind::InvalidNumber("-2".to_string()))
+        );
+        assert_eq!(delimited::<u8>("1, 1,3", ',', 1).unwrap(), [1, 1, 3]);
+        assert_eq!(
+            parse_error(delimited::<u8>("1,,3", ',', 9)),
+            (9, ParseErrorKind::InvalidNumber(String::new()))
+        );
+    }
+
+    #[test]
+    fn extracts_signed_and_unsigned_numbers() {
+        let text = "Card  1: x=-3, y=4 | 10-12";
+
+        assert_eq!(extract_numbers::<i64>(text), [1, -3, 4, 10, -12]);
+        assert_eq!(extract_numbers::<u64>(text), [1, 3, 4, 10, 12]);
+        assert_eq!(extract_numbers::<u8>("255 256 -"), [255]);
+        assert!(extract_numbers::<u32>("no digits - here").is_empty());
+    }
+
+    #[test]
+    fn splits_pairs_and_tokens() {
+        assert_eq!(
+            pair("AAA = (BBB, CCC)", '=', 1).unwrap(),
+            ("AAA ", " (BBB, CCC)")
+        );
+        assert_eq!(key_values(" Time:  7 15 ", 1).unwrap(), ("Time", "7 15"));
+        assert_eq!(
+            parse_error(key_values("Time 7", 2)),
+            (2, ParseErrorKind::MissingSeparator(':'))
+        );
+        assert_eq!(tokens("(BBB, CCC)", &['(', ')', ',', ' ']), ["BBB", "CCC"]);
+    }
+
+    #[test]
+    fn reads_rectangular_grids_only() {
+        let cell = |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None,
+        };
+
+        assert_eq!(
+            grid(input(".#\n#.\n"), cell).unwrap(),
+            [[false, true], [true, false]]
+        );
+        assert_eq!(
+            parse_error(grid(input(".#\n#\n"), cell)),
+            (
+                2,
+                ParseErrorKind::RaggedRow {
+                    expected: 2,
+                    found: 1
+                }
+            )
+        );
+        assert_eq!(
+            parse_error(grid_text("..\n.x", cell)),
+            (2, ParseErrorKind::UnexpectedChar('x'))
+        );
+        assert_eq!(grid_rows(input(""), cell, |_| Ok(())).unwrap(), None);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+};
+
+use crate::fetch::http_get;
+
+pub const PUZZLE_DIR: &str = ".aoc/puzzles";
+
+pub fn puzzle_path(day: u8) -> PathBuf {
+    PathBuf::from(PUZZLE_DIR).join(format!("day{:02}.html", day))
+}
+
+pub fn store(day: u8, html: &[u8]) -> io::Result<()> {
+    fs::create_dir_all(PUZZLE_DIR)?;
+    fs::write(puzzle_path(day), html)
+}
+
+pub fn load(day: u8) -> io::Result<String> {
+    fs::read_to_string(puzzle_path(day))
+}
+
+/// Downloads `{base_url}/day/{day}`; `base_url` must be a plain `http://`
+/// mirror, see [`http_get`].
+pub fn fetch(base_url: &str, day: u8, session: Option<&str>) -> io::Result<Vec<u8>> {
+    http_get(
+        &format!("{}/day/{}", base_url.trim_end_matches('/'), day),
+        session,
+    )
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Converts the `<article>` elements of a puzzle page to Markdown, ignoring
+/// everything outside them.
+pub fn html_to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    let mut article_depth = 0;
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links: Vec<String> = Vec::new();
+
+    while let Some(tag_start) = rest.find('<') {
+        let text = &rest[..tag_start];
+        if article_depth > 0 {
+            if in_pre {
+                out.push_str(&decode_entities(text));
+            } else {
+                let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if text.starts_with(char::is_whitespace) && !out.ends_with([' ', '\n']) {
+                    out.push(' ');
+                }
+                out.push_str(&decode_entities(&collapsed));
+                if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+                    out.push(' ');
+                }
+            }
+        }
+
+        let tag_end = match rest[tag_start..].find('>') {
+            Some(end) => tag_start + end,
+            None => break,
+        };
+        let tag = &rest[tag_start + 1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if name == "article" {
+            if closing {
+                article_depth -= 1;
+                out.push_str("\n\n");
+            } else {
+                article_depth += 1;
+            }
+            continue;
+        }
+        if article_depth == 0 {
+            continue;
+        }
+
+        match (name.as_str(), closing) {
+            ("h2", false) => out.push_str("## "),
+            ("h2", true) | ("p", true) => out.push_str("\n\n"),
+            ("em", _) if !in_code && !in_pre => out.push('*'),
+            ("code", _) if !in_pre => {
+                in_code = !closing;
+                out.push('`');
+            }
+            ("pre", false) => {
+                in_pre = true;
+                out.push_str("```\n");
+            }
+            ("pre", true) => {
+                in_pre = false;
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n\n");
+            }
+            ("li", false) => out.push_str("- "),
+            ("li", true) | ("ul", true) | ("br", _) => out.push('\n'),
+            ("a", false) => {
+                links.push(attribute(tag, "href").unwrap_or_default().to_string());
+                out.push('[');
+            }
+            ("a", true) => {
+                let href = links.pop().unwrap_or_default();
+                out.push_str(&format!("]({})", href));
+            }
+            _ => {}
+        }
+    }
+
+    let mut markdown = String::new();
+    for line in out.lines() {
+        let line = line.trim_end();
+        if line.is_empty() && (markdown.is_empty() || markdown.ends_with("\n\n")) {
+            continue;
+        }
+        markdown.push_str(line);
+        markdown.push('\n');
+    }
+
+    markdown.trim_end().to_string()
+}
+
+/// Prints Markdown to stdout, highlighting headings when it is a terminal.
+pub fn render(markdown: &str) {
+    let styled = io::stdout().is_terminal();
+    for line in markdown.lines() {
+        match line.strip_prefix("## ") {
+            Some(heading) if styled => println!("\x1b[1m{}\x1b[0m", heading),
+            _ => println!("{}", line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_articles_to_markdown() {
+        let markdown = html_to_markdown(include_str!("../tests/fixtures/puzzle.html"));
+
+        assert_eq!(
+            markdown,
+            "## --- Day 1: Trebuchet?! ---
+
+Something is wrong with global snow production, and you've been selected to take a look.
+
+The newly-improved calibration document consists of lines of text; each line originally contained a specific *calibration value* that the Elves now need to recover. On each line, the calibration value can be found by combining the *first digit* and the *last digit* (in that order) to form a single *two-digit number*.
+
+For example:
+
+```
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+```
+
+In this example, the calibration values of these four lines are:
+
+- `12` from the first line,
+- `38` from `pqr3stu8vwx` & so on.
+
+Read more about [trebuchets](https://en.wikipedia.org/wiki/Trebuchet) and add them up to get `142`."
+        );
+    }
+
+    #[test]
+    fn ignores_everything_outside_articles() {
+        let html = "<p>before</p><article><p>inside</p></article><p>after</p>";
+
+        assert_eq!(html_to_markdown(html), "inside");
+        assert_eq!(html_to_markdown("<p>no article</p>"), "");
+    }
+}
@@ -1,7 +1,16 @@
-use std::{io::{Lines, BufReader}, fs::File};
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::Stat,
+    parse,
+    read_input::Input,
+};
 
 #[derive(Debug)]
-struct Gear {
+pub struct Gear {
     position: Point,
 }
 
@@ -10,7 +19,7 @@ impl Gear {
         Self { position }
     }
 
-    fn find_ships_nearby_by_one_point<'a>(&'a self, ships: &'a Vec<Ship>) -> Vec<&Ship> {
+    fn find_ships_nearby_by_one_point<'a>(&'a self, ships: &'a Vec<Ship>) -> Vec<&'a Ship> {
         let mut ships_nearby = Vec::new();
         let x = self.position.x;
         let y = self.position.y;
@@ -33,7 +42,7 @@ impl Gear {
 }
 
 #[derive(Debug, Clone)]
-struct Ship {
+pub struct Ship {
     position: Vec<Point>,
 }
 
@@ -48,13 +57,18 @@ impl Ship {
         self.position.push(point);
     }
 
-    fn get_value(&self) -> isize {
-        let mut value_str = String::new();
-        for point in &self.position {
-            value_str.push(point.c);
-        }
+    fn digits(&self) -> String {
+        self.position.iter().map(|point| point.c).collect()
+    }
+
+    fn get_value(&self) -> Result<i64> {
+        let value_str = self.digits();
+        let row: usize = checked::cast(self.position[0].x, "q03 row")?;
+        let line = checked::add(row, 1, "q03 line number")?;
 
-        value_str.parse::<isize>().unwrap()
+        value_str
+            .parse::<i64>()
+            .map_err(|_| Error::parse(line, format!("part number {} is out of range", value_str)))
     }
 }
 
@@ -69,25 +83,30 @@ impl Point {
     fn new(x: isize, y: isize, c: char) -> Self {
         Self { x, y, c }
     }
-}
 
-pub fn part2(lines: Lines<BufReader<File>>) {
+    /// 1-based `[row, column]`.
+    fn at(&self) -> [isize; 2] {
+        [self.x + 1, self.y + 1]
+    }
+}
 
+pub fn load_schematic(lines: Input) -> Result<(Vec<Ship>, Vec<Gear>)> {
     let mut in_ship = false;
     let mut ships = Vec::new();
     let mut gears = Vec::new();
     let mut ship = Ship::new();
 
-    for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-        for (j, c) in line.chars().enumerate() {
-            if c.is_alphanumeric() {
+    for (i, row) in parse::grid(lines, Some)?.into_iter().enumerate() {
+        let x = checked::cast(i, "q03 row")?;
+        for (j, c) in row.into_iter().enumerate() {
+            let y = checked::cast(j, "q03 column")?;
+            if c.is_ascii_digit() {
                 in_ship = true;
-                let point = Point::new(i as isize, j as isize, c);
+                let point = Point::new(x, y, c);
                 ship.add(point);
             } else {
                 if c == '*' {
-                    gears.push(Gear::new(Point::new(i as isize, j as isize, c)));
+                    gears.push(Gear::new(Point::new(x, y, c)));
                 }
                 if in_ship {
                     in_ship = false;
@@ -104,15 +123,88 @@ pub fn part2(lines: Lines<BufReader<File>>) {
         }
     }
 
-    let mut sum = 0;
+    Ok((ships, gears))
+}
+
+pub fn part2(lines: Input) -> Result<Answer> {
+    let (ships, gears) = load_schematic(lines)?;
+
+    let mut sum: i64 = 0;
     for gear in gears {
+        cancel::check()?;
         let shippo = gear.find_ships_nearby_by_one_point(&ships);
+        let _gear = crate::trace_span!(
+            "gear",
+            row = gear.position.x + 1,
+            column = gear.position.y + 1,
+            ships = shippo.len(),
+        );
+        for ship in &shippo {
+            crate::trace!(
+                "ship",
+                number = ship.digits(),
+                row = ship.position[0].x + 1,
+                column = ship.position[0].y + 1,
+            );
+        }
         if shippo.len() == 2 {
-            let summo = shippo[0].get_value() * shippo[1].get_value();
-            sum += summo;
+            let summo = shippo[0]
+                .get_value()?
+                .checked_mul(shippo[1].get_value()?)
+                .ok_or(Error::Overflow("q03 gear ratio"))?;
+            sum = sum
+                .checked_add(summo)
+                .ok_or(Error::Overflow("q03 gear ratio sum"))?;
         }
     }
 
-    println!("{}", sum);
+    Ok(sum.into())
+}
+
+/// Every part number and gear with its 1-based `[row, column]`, the gears
+/// listing the part numbers next to them.
+pub fn dump(lines: Input) -> Result<Value> {
+    let (ships, gears) = load_schematic(lines)?;
+    let part_numbers = ships
+        .iter()
+        .map(|ship| {
+            Ok(json!({
+                "number": ship.get_value()?,
+                "at": ship.position[0].at(),
+            }))
+        })
+        .collect::<Result<Vec<Value>>>()?;
+    let gears = gears
+        .iter()
+        .map(|gear| {
+            let adjacent = gear
+                .find_ships_nearby_by_one_point(&ships)
+                .iter()
+                .map(|ship| ship.get_value())
+                .collect::<Result<Vec<i64>>>()?;
+            Ok(json!({
+                "at": gear.position.at(),
+                "part_numbers": adjacent,
+            }))
+        })
+        .collect::<Result<Vec<Value>>>()?;
+
+    Ok(json!({
+        "part_numbers": part_numbers,
+        "gears": gears,
+    }))
+}
 
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let (ships, gears) = load_schematic(lines)?;
+    let ratios = gears
+        .iter()
+        .filter(|gear| gear.find_ships_nearby_by_one_point(&ships).len() == 2)
+        .count();
+
+    Ok(vec![
+        ("part numbers", ships.len().to_string()),
+        ("gears", gears.len().to_string()),
+        ("gears next to two numbers", ratios.to_string()),
+    ])
 }
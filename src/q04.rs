@@ -1,6 +1,14 @@
-use std::{
-    fs::File,
-    io::{BufReader, Lines},
+use std::collections::VecDeque;
+
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    parse,
+    read_input::Input,
+    validate::Violation,
 };
 
 #[derive(Debug)]
@@ -11,73 +19,182 @@ struct Game<'a> {
 }
 
 impl<'a> Game<'a> {
-    fn new(winning_numbers: &'a [u32], guesses: &'a [u32]) -> Self {
-        Game {
+    fn new(winning_numbers: &'a [u32], guesses: &'a [u32]) -> Result<Self> {
+        Ok(Game {
             winning_numbers,
             guesses,
             found: {
                 let mut found = 0;
                 for guess in guesses {
                     if winning_numbers.contains(guess) {
-                        found += 1;
+                        found = checked::add(found, 1, "q04 matching numbers")?;
                     }
                 }
 
                 found
             },
-        }
+        })
     }
 
-    fn calculate_points(&self) -> u32 {
+    fn calculate_points(&self) -> Result<u32> {
         if self.found > 2 {
-            return 2_u32.pow(self.found - 1);
+            return 2_u32
+                .checked_pow(self.found - 1)
+                .ok_or(Error::Overflow("q04 card points"));
         }
 
-        self.found
+        Ok(self.found)
     }
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
-    let mut sum = 0;
-    for line in lines {
-        let line = line.unwrap();
-        let spl = line
-            .split(' ')
-            .skip(2)
-            .filter_map(|x| x.parse::<u32>().ok())
-            .collect::<Vec<u32>>();
-        let (winning_numbers, guesses) = spl.split_at(10);
-        let game = Game::new(winning_numbers, guesses);
-        sum += game.calculate_points();
+/// A card's winning numbers and the numbers it has, either side of the `|`.
+fn parse_card(line: &str, line_number: usize) -> Result<(Vec<u32>, Vec<u32>)> {
+    let (_, numbers) = parse::key_values(line, line_number)?;
+    let (winning_numbers, guesses) = parse::pair(numbers, '|', line_number)?;
+
+    Ok((
+        parse::extract_numbers(winning_numbers),
+        parse::extract_numbers(guesses),
+    ))
+}
+
+/// Parses one card and counts its matching numbers.
+fn card_matches(line: &str, line_number: usize) -> Result<u32> {
+    let (winning_numbers, guesses) = parse_card(line, line_number)?;
+    Ok(Game::new(&winning_numbers, &guesses)?.found)
+}
+
+pub fn load_cards(lines: Input) -> Result<Vec<(Vec<u32>, Vec<u32>)>> {
+    let mut cards = Vec::new();
+    for (i, line) in lines.enumerate() {
+        cards.push(parse_card(&line?, i + 1)?);
     }
 
-    println!("Sum: {}", sum);
+    Ok(cards)
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    let mut founds: Vec<u32> = Vec::new();
-    for line in lines {
-        let line = line.unwrap();
-        let spl = line
-            .split(' ')
-            .skip(2)
-            .filter_map(|x| x.parse::<u32>().ok())
-            .collect::<Vec<u32>>();
-        let (wn, gs) = spl.split_at(10);
-        let game = Game::new(wn, gs);
-        let found = game.found;
-        founds.push(found);
+pub fn part1(lines: Input) -> Result<Answer> {
+    let mut sum: u64 = 0;
+    for (i, line) in lines.enumerate() {
+        cancel::check()?;
+        let (winning_numbers, guesses) = parse_card(&line?, i + 1)?;
+        let game = Game::new(&winning_numbers, &guesses)?;
+        sum = sum
+            .checked_add(u64::from(game.calculate_points()?))
+            .ok_or(Error::Overflow("q04 points sum"))?;
     }
 
-    let mut amounts: Vec<u32> = vec![1; founds.len()];
+    Ok(sum.into())
+}
+
+/// Streams the cards, only remembering copies won for the cards still to come.
+pub fn part2(lines: Input) -> Result<Answer> {
+    // copies[k] is the number of extra copies of the card k places ahead
+    let mut copies: VecDeque<u64> = VecDeque::new();
+    // cards whose winnings reach past the cards read so far, as (last index won, line)
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut total: u64 = 0;
+
+    for (i, line) in lines.enumerate() {
+        cancel::check()?;
+        let found: usize = checked::cast(card_matches(&line?, i + 1)?, "q04 matching numbers")?;
+        let amount = copies
+            .pop_front()
+            .unwrap_or(0)
+            .checked_add(1)
+            .ok_or(Error::Overflow("q04 card copies"))?;
+        total = total
+            .checked_add(amount)
+            .ok_or(Error::Overflow("q04 card count"))?;
 
-    for i in 0..founds.len() {
-        for j in 1..founds[i] + 1 {
-            amounts[i + j as usize] += amounts[i];
+        if copies.len() < found {
+            copies.resize(found, 0);
+        }
+        for extra in copies.iter_mut().take(found) {
+            *extra = extra
+                .checked_add(amount)
+                .ok_or(Error::Overflow("q04 card copies"))?;
+        }
+
+        open.retain(|&(last_won, _)| last_won > i);
+        if found > 0 {
+            open.push((i + found, i + 1));
         }
     }
 
-    let sum = amounts.iter().sum::<u32>();
-    println!("Sum: {}", sum);
+    if let Some(&(_, line)) = open.first() {
+        return Err(Error::parse(line, "card wins copies past the end of the table"));
+    }
+
+    Ok(total.into())
+}
+
+/// Every card's winning numbers, the numbers it has and how many of them
+/// match.
+pub fn dump(lines: Input) -> Result<Value> {
+    let cards = load_cards(lines)?
+        .iter()
+        .enumerate()
+        .map(|(i, (winning_numbers, guesses))| {
+            let game = Game::new(winning_numbers, guesses)?;
+            Ok(json!({
+                "card": i + 1,
+                "winning": game.winning_numbers,
+                "numbers": game.guesses,
+                "matches": game.found,
+            }))
+        })
+        .collect::<Result<Vec<Value>>>()?;
+
+    Ok(json!({ "cards": cards }))
+}
+
+pub fn validate(lines: Input) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    let mut founds = Vec::new();
+    // every card has as many winning numbers as the first
+    let mut winning_count = None;
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let numbers = line.split_once(':').map_or(line.as_str(), |(_, n)| n);
+        let (winning, guesses) = match parse::pair(numbers, '|', i + 1) {
+            Ok(halves) => halves,
+            Err(e) => {
+                violations.push(Violation::from_error(e));
+                founds.push(0);
+                continue;
+            }
+        };
+
+        let winning: Vec<u32> = parse::extract_numbers(winning);
+        let expected = *winning_count.get_or_insert(winning.len());
+        if winning.len() != expected {
+            violations.push(Violation::at(
+                i + 1,
+                format!(
+                    "expected {} winning numbers, found {}",
+                    expected,
+                    winning.len()
+                ),
+            ));
+        }
+
+        let found = parse::extract_numbers::<u32>(guesses)
+            .iter()
+            .filter(|x| winning.contains(x))
+            .count();
+        founds.push(found);
+    }
+
+    for (i, found) in founds.iter().enumerate() {
+        if i + found >= founds.len() {
+            violations.push(Violation::at(
+                i + 1,
+                format!("card wins {} copies past the end of the table", i + found + 1 - founds.len()),
+            ));
+        }
+    }
 
+    Ok(violations)
 }
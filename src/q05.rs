@@ -1,10 +1,17 @@
-use std::{
-    fs::File,
-    io::{BufReader, Lines},
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::Stat,
+    interval::{Interval, RangeSet},
+    parse,
+    read_input::Input,
 };
 
 #[derive(Debug)]
-struct Seed {
+pub struct Seed {
     val: u32,
 }
 
@@ -15,121 +22,174 @@ impl Seed {
 }
 
 #[derive(Debug)]
-struct SeedMapping {
-    destination: usize,
-    source: usize,
-    steps: usize,
+pub struct SeedMapping {
+    source: Interval<usize>,
+    offset: i128,
 }
 
 impl SeedMapping {
-    fn new(destination: usize, source: usize, steps: usize) -> Self {
-        SeedMapping {
-            destination,
-            source,
-            steps,
-        }
+    /// `None` for a mapping that covers no seeds.
+    fn new(destination: usize, source: usize, steps: usize) -> Option<Self> {
+        Some(SeedMapping {
+            source: Interval::with_len(source, steps as u128)?,
+            offset: destination as i128 - source as i128,
+        })
     }
 
-    fn path(&self, seed: usize) -> usize {
-        if self.source <= seed && self.source + self.steps > seed {
-            let ret = (self.destination as isize - self.source as isize + seed as isize) as usize;
-            return ret;
-        }
-        seed
+    /// Where a seed inside `source` ends up.
+    fn apply(&self, seed: usize) -> Result<usize> {
+        checked::cast(seed as i128 + self.offset, "q05 seed mapping")
     }
-}
 
-fn load_seeds(lines: Lines<BufReader<File>>) -> (Vec<Seed>, Vec<Vec<SeedMapping>>) {
-    let mut maps: Vec<Vec<SeedMapping>> = Vec::new();
-    let mut seeds: Vec<Seed> = Vec::new();
-
-    let mut index = 0;
-    for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-        if i == 0 {
-            let (_, second_half) = line.split_once(':').unwrap();
-
-            seeds = second_half
-                .split_whitespace()
-                .map(|x| Seed::new(x.parse::<u32>().unwrap()))
-                .collect();
-            continue;
-        }
-
-        if line.is_empty() {
-            continue;
-        }
+    /// The mapping as its input line had it.
+    fn to_json(&self) -> Result<Value> {
+        Ok(json!({
+            "destination": self.apply(self.source.start())?,
+            "source": self.source.start(),
+            "length": self.source.len() as u64,
+        }))
+    }
+}
 
-        if line.contains(':') {
-            index += 1;
-            maps.push(Vec::new());
-            continue;
+/// Sends every seed in `seeds` through one map: seeds inside a mapping's
+/// source move with it, the rest keep their number.
+fn map_ranges(seeds: &RangeSet<usize>, mappings: &[SeedMapping]) -> Result<RangeSet<usize>> {
+    let mut unmapped = seeds.clone();
+    let mut mapped = Vec::new();
+
+    for mapping in mappings {
+        cancel::check()?;
+        let source: RangeSet<usize> = [mapping.source].into_iter().collect();
+        let moving = unmapped.intersection(&source);
+        // load_seeds rejects mappings whose destination overflows
+        for interval in moving.intervals() {
+            let shifted = interval.shift(mapping.offset).expect("validated mapping");
+            crate::trace!("hop", from = interval.to_string(), to = shifted.to_string());
+            mapped.push(shifted);
         }
-
-        let v: Vec<usize> = line
-            .split_whitespace()
-            .map(|x| x.parse::<usize>().unwrap())
-            .collect();
-
-        maps[index - 1].push(SeedMapping::new(v[0], v[1], v[2]));
+        unmapped = unmapped.difference(&source);
     }
 
-    (seeds, maps)
+    Ok(mapped
+        .into_iter()
+        .chain(unmapped.intervals().copied())
+        .collect())
 }
 
+pub fn load_seeds(lines: Input) -> Result<(Vec<Seed>, Vec<Vec<SeedMapping>>)> {
+    let mut maps: Vec<Vec<SeedMapping>> = Vec::new();
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    println!("part2");
-    let mut min: usize = 999999999999999;
-    let (seeds, maps) = load_seeds(lines);
+    let blocks = parse::blocks(lines)?;
+    let (first, sections) = blocks
+        .split_first()
+        .ok_or_else(|| Error::parse(1, "expected `seeds:` header"))?;
+
+    let (_, second_half) = parse::key_values(&first.lines[0], first.first_line)?;
+    let seeds = parse::numbers::<u32>(second_half, first.first_line)?
+        .into_iter()
+        .map(Seed::new)
+        .collect();
+    if first.lines.len() > 1 {
+        return Err(Error::parse(
+            first.first_line + 1,
+            "mapping line before any map header",
+        ));
+    }
 
-    for i in (0..seeds.len()).step_by(2) {
-        println!("i: {}", i);
-        let seed_range = seeds[i].val as usize..=seeds[i].val as usize + seeds[i + 1].val as usize;
-        println!("seed_range: {:?}", seed_range);
+    for section in sections {
+        if !section.lines[0].contains(':') {
+            return Err(Error::parse(section.first_line, "expected a map header"));
+        }
 
-        for seed in seed_range {
-            let mut val = seed;
+        let mut mappings = Vec::new();
+        for (offset, line) in section.lines.iter().enumerate().skip(1) {
+            let line_number = section.first_line + offset;
+            let v: Vec<usize> = parse::numbers(line, line_number)?;
 
-            for innermaps in &maps {
-                for map in innermaps {
-                    let old_val = val;
-                    val = map.path(val);
-                    if val != old_val {
-                        break;
-                    }
-                }
+            if v.len() != 3 {
+                return Err(Error::parse(line_number, "expected destination, source and length"));
             }
-
-            if val < min {
-                min = val;
+            if v[0].checked_add(v[2]).is_none() || v[1].checked_add(v[2]).is_none() {
+                return Err(Error::parse(line_number, "mapping range overflows"));
             }
+
+            mappings.extend(SeedMapping::new(v[0], v[1], v[2]));
         }
+        maps.push(mappings);
+    }
+
+    Ok((seeds, maps))
+}
+
+pub fn part2(lines: Input) -> Result<Answer> {
+    let (seeds, maps) = load_seeds(lines)?;
+    if seeds.len() % 2 != 0 {
+        return Err(Error::parse(1, "seed ranges must come in pairs"));
+    }
+
+    let mut ranges: RangeSet<usize> = seeds
+        .chunks(2)
+        .filter_map(|pair| Interval::with_len(pair[0].val as usize, pair[1].val as u128))
+        .collect();
+    for (k, mappings) in maps.iter().enumerate() {
+        let _map = crate::trace_span!("map", index = k + 1, ranges = ranges.intervals().count());
+        ranges = map_ranges(&ranges, mappings)?;
     }
 
-    println!("min: {}", min);
+    ranges
+        .min()
+        .map(Answer::from)
+        .ok_or_else(|| Error::NoSolution("no seeds".to_string()))
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
+pub fn part1(lines: Input) -> Result<Answer> {
     let mut values: Vec<usize> = Vec::new();
-    let (seeds, maps) = load_seeds(lines);
+    let (seeds, maps) = load_seeds(lines)?;
 
     for seed in seeds.iter() {
         let mut val = seed.val as usize;
-
-        for innermaps in &maps {
-            for map in innermaps {
-                let old_val = val;
-                val = map.path(val);
-                if val != old_val {
-                    break;
-                }
+        let _seed = crate::trace_span!("seed", value = val);
+
+        for (k, innermaps) in maps.iter().enumerate() {
+            cancel::check()?;
+            if let Some(map) = innermaps.iter().find(|map| map.source.contains(val)) {
+                let next = map.apply(val)?;
+                crate::trace!("hop", map = k + 1, from = val, to = next);
+                val = next;
             }
         }
 
         values.push(val);
     }
 
-    let min = values.iter().min().unwrap();
-    println!("min: {}", min);
+    values
+        .iter()
+        .min()
+        .map(|&value| Answer::from(value))
+        .ok_or_else(|| Error::NoSolution("no seeds".to_string()))
+}
+
+/// The seeds and every map's mappings, in input order.
+pub fn dump(lines: Input) -> Result<Value> {
+    let (seeds, maps) = load_seeds(lines)?;
+    let maps = maps
+        .iter()
+        .map(|mappings| mappings.iter().map(SeedMapping::to_json).collect())
+        .collect::<Result<Vec<Vec<Value>>>>()?;
+
+    Ok(json!({
+        "seeds": seeds.iter().map(|seed| seed.val).collect::<Vec<_>>(),
+        "maps": maps,
+    }))
+}
+
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let (seeds, maps) = load_seeds(lines)?;
+    let sizes: Vec<String> = maps.iter().map(|map| map.len().to_string()).collect();
+
+    Ok(vec![
+        ("seeds", seeds.len().to_string()),
+        ("maps", maps.len().to_string()),
+        ("mappings per map", sizes.join(", ")),
+    ])
 }
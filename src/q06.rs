@@ -1,71 +1,118 @@
-use std::{
-    fs::File,
-    io::{BufReader, Lines},
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    checked,
+    error::{Error, Result},
+    math, parse,
+    read_input::{collect_text, input_from_bytes, Input},
 };
 
-pub fn part1(lines: Lines<BufReader<File>>) {
+pub fn load_races(lines: Input) -> Result<(Vec<u64>, Vec<u64>)> {
     let mut times: Vec<u64> = Vec::new();
     let mut distances: Vec<u64> = Vec::new();
 
     for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-        let second_half = line.split_once(':').unwrap().1;
+        let line = line?;
+        let (_, second_half) = parse::key_values(&line, i + 1)?;
 
         if i == 0 {
-            times = second_half
-                .split(' ')
-                .filter_map(|x| x.parse::<u64>().ok())
-                .collect::<Vec<u64>>();
+            times = parse::extract_numbers(second_half);
         } else {
-            distances = second_half
-                .split(' ')
-                .filter_map(|x| x.parse::<u64>().ok())
-                .collect::<Vec<u64>>();
+            distances = parse::extract_numbers(second_half);
         }
     }
 
-    product(times, distances);
+    if times.len() != distances.len() {
+        return Err(Error::parse(2, "every race needs a time and a distance"));
+    }
+
+    Ok((times, distances))
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
+pub fn load_kerned_race(lines: Input) -> Result<(u64, u64)> {
     let mut times: Vec<u64> = Vec::new();
     let mut distances: Vec<u64> = Vec::new();
 
     for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-        let second_half = line.split_once(':').unwrap().1.replace(' ', "").parse::<u64>().unwrap();
-        println!("{}", second_half);
+        let line = line?;
+        let (_, second_half) = parse::key_values(&line, i + 1)?;
+        let second_half = parse::numbers::<u64>(&second_half.replace(' ', ""), i + 1)?;
 
         if i == 0 {
-            times = vec![second_half];
+            times = second_half;
         } else {
-            distances = vec![second_half];
+            distances = second_half;
         }
     }
 
-    product(times, distances);
+    match (times.as_slice(), distances.as_slice()) {
+        (&[time], &[distance]) => Ok((time, distance)),
+        _ => Err(Error::parse(1, "expected a time line and a distance line")),
+    }
 }
 
-fn product(times: Vec<u64>, distances: Vec<u64>) {
-    let mut vec = Vec::new();
+pub fn part1(lines: Input) -> Result<Answer> {
+    let (times, distances) = load_races(lines)?;
+    product(times, distances)
+}
+
+pub fn part2(lines: Input) -> Result<Answer> {
+    let (time, distance) = load_kerned_race(lines)?;
+    product(vec![time], vec![distance])
+}
 
-    for (i, time) in times.into_iter().enumerate() {
-        let mut move_sum = 0;
-        for t in 1..time {
-            let speed = t;
-            let time = time - t;
+fn product(times: Vec<u64>, distances: Vec<u64>) -> Result<Answer> {
+    times
+        .into_iter()
+        .zip(distances)
+        .try_fold(1_u64, |product, (time, distance)| {
+            product
+                .checked_mul(winning_holds(time, distance)?)
+                .ok_or(Error::Overflow("q06 product"))
+        })
+        .map(Answer::from)
+}
 
-            let move_dist = speed * time;
+/// Number of hold times `t` with `t * (time - t) > distance`, found from the
+/// roots of the quadratic instead of trying every `t`.
+fn winning_holds(time: u64, distance: u64) -> Result<u64> {
+    let (time, distance) = (time as u128, distance as u128);
+    let beats = |t: u128| t * (time - t) > distance;
 
-            if move_dist > distances[i] {
-                move_sum += 1;
-            }
-        }
+    if time * time <= 4 * distance {
+        return Ok(0);
+    }
 
-        vec.push(move_sum);
+    let root = math::isqrt(time * time - 4 * distance);
+    let mut low = (time - root) / 2;
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+    while low <= time / 2 && !beats(low) {
+        low += 1;
     }
+    if low > time / 2 {
+        return Ok(0);
+    }
+
+    checked::cast(time - 2 * low + 1, "q06 winning holds")
+}
 
-    let mutliplied = vec.iter().product::<i32>();
+/// The races as part 1 reads them and the single race part 2 reads once the
+/// spaces between digits are dropped.
+pub fn dump(lines: Input) -> Result<Value> {
+    let text = collect_text(lines)?;
+    let (times, distances) = load_races(input_from_bytes(text.clone().into_bytes()))?;
+    let (time, distance) = load_kerned_race(input_from_bytes(text.into_bytes()))?;
+    let races: Vec<Value> = times
+        .iter()
+        .zip(&distances)
+        .map(|(time, distance)| json!({ "time": time, "distance": distance }))
+        .collect();
 
-    println!("Multiplied: {}", mutliplied);
+    Ok(json!({
+        "races": races,
+        "kerned_race": { "time": time, "distance": distance },
+    }))
 }
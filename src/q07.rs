@@ -1,10 +1,24 @@
-use std::{
-    cmp::Ordering,
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Lines},
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::{self, Stat},
+    parse,
+    query::{self, Query},
+    read_input::{collect_text, input_text, Input},
+    validate::Violation,
 };
 
+pub const QUERIES: &[Query] = &[Query {
+    name: "hand",
+    usage: "hand LINE",
+    run: query_hand,
+}];
+
 #[derive(Debug)]
 struct Game<T: CardTrait> {
     hands: Vec<Hand<T>>,
@@ -64,8 +78,8 @@ impl<T: CardTrait> Hand<T> {
         self.cards.push(card);
     }
 
-    fn set_hand_type(&mut self, strategy: &dyn HandTypeSettingStrategy<T>) {
-        strategy.set_hand_type(self);
+    fn set_hand_type(&mut self, strategy: &dyn HandTypeSettingStrategy<T>) -> Result<()> {
+        strategy.set_hand_type(self)
     }
 }
 
@@ -81,12 +95,12 @@ enum HandType {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Debug)]
-struct Card {
+pub struct Card {
     value: char,
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Debug)]
-struct ModifiedCard {
+pub struct ModifiedCard {
     value: char,
 }
 
@@ -97,20 +111,19 @@ pub trait CardTrait {
 }
 
 pub trait HandTypeSettingStrategy<T: CardTrait> {
-    fn set_hand_type(&self, hand: &mut Hand<T>);
+    fn set_hand_type(&self, hand: &mut Hand<T>) -> Result<()>;
 }
 
-struct Q1Strategy;
-struct Q2Strategy;
+pub struct Q1Strategy;
+pub struct Q2Strategy;
 
 impl<T: CardTrait> HandTypeSettingStrategy<T> for Q1Strategy {
-    fn set_hand_type(&self, hand: &mut Hand<T>) {
+    fn set_hand_type(&self, hand: &mut Hand<T>) -> Result<()> {
         let mut set: HashMap<char, usize> = HashMap::new();
 
         for card in hand.cards.iter() {
-            set.entry(card.value_char())
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
+            let count = set.entry(card.value_char()).or_insert(0);
+            *count = checked::add(*count, 1, "q07 card count")?;
         }
 
         let values = set.values();
@@ -126,17 +139,18 @@ impl<T: CardTrait> HandTypeSettingStrategy<T> for Q1Strategy {
             [5] => HandType::FiveOfAKind,
             _ => HandType::HighCard,
         };
+
+        Ok(())
     }
 }
 
 impl<T: CardTrait> HandTypeSettingStrategy<T> for Q2Strategy {
-    fn set_hand_type(&self, hand: &mut Hand<T>) {
+    fn set_hand_type(&self, hand: &mut Hand<T>) -> Result<()> {
         let mut set: HashMap<char, usize> = HashMap::new();
 
         for card in hand.cards.iter() {
-            set.entry(card.value_char())
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
+            let count = set.entry(card.value_char()).or_insert(0);
+            *count = checked::add(*count, 1, "q07 card count")?;
         }
 
         let values = set.values();
@@ -192,6 +206,8 @@ impl<T: CardTrait> HandTypeSettingStrategy<T> for Q2Strategy {
                 }
             }
         };
+
+        Ok(())
     }
 }
 
@@ -237,39 +253,194 @@ impl CardTrait for Card {
     }
 }
 
-pub fn run<T: CardTrait + std::cmp::Eq>(lines: Lines<BufReader<File>>, strategy: &dyn HandTypeSettingStrategy<T>) {
+pub fn load_hands<T: CardTrait>(
+    text: &str,
+    strategy: &dyn HandTypeSettingStrategy<T>,
+) -> Result<Vec<Hand<T>>> {
     let mut hands: Vec<Hand<T>> = Vec::new();
-    for line in lines {
-        let line = line.unwrap();
-        let (cards, vals) = line.split_once(' ').unwrap();
+    for (i, line) in text.lines().enumerate() {
+        cancel::check()?;
+        let (cards, vals) = parse::pair(line, ' ', i + 1)?;
 
-        let point = vals.parse::<u32>().unwrap();
+        let point = parse::number::<u32>(vals, i + 1)?;
 
         let mut hand = Hand::new(point);
         for card in cards.chars() {
+            if !"AKQJT98765432".contains(card) {
+                return Err(Error::parse(i + 1, format!("invalid card {}", card)));
+            }
             let card = T::new(card);
             hand.add_card(card);
         }
+        if hand.cards.len() != 5 {
+            return Err(Error::parse(
+                i + 1,
+                format!("expected 5 cards, found {}", hand.cards.len()),
+            ));
+        }
 
-        hand.set_hand_type(strategy);
+        hand.set_hand_type(strategy)?;
+        crate::trace!(
+            "hand",
+            line = i + 1,
+            cards = hand.cards.iter().map(T::value_char).collect::<String>(),
+            bid = point,
+            kind = format!("{:?}", hand.hand_type),
+        );
         hands.push(hand);
     }
 
+    Ok(hands)
+}
+
+pub fn run<T: CardTrait + std::cmp::Eq>(
+    text: &str,
+    strategy: &dyn HandTypeSettingStrategy<T>,
+) -> Result<Answer> {
+    let hands = load_hands(text, strategy)?;
+
     let mut game = Game::new(hands);
     game.order_games();
 
-    let mut sum = 0;
+    let mut sum: u64 = 0;
     for (i, hand) in game.hands.iter().enumerate() {
-        sum += hand.point * (i as u32 + 1);
+        sum = (i as u64 + 1)
+            .checked_mul(u64::from(hand.point))
+            .and_then(|winnings| sum.checked_add(winnings))
+            .ok_or(Error::Overflow("q07 winnings"))?;
+    }
+
+    Ok(sum.into())
+}
+
+/// The hand on `line` under `strategy`: its kind and where it ranks.
+fn describe_hand<T: CardTrait + Eq>(
+    input: &[u8],
+    strategy: &dyn HandTypeSettingStrategy<T>,
+    line: usize,
+) -> Result<Option<String>> {
+    let hands = load_hands(input_text(input)?, strategy)?;
+    let hand = match line.checked_sub(1).and_then(|i| hands.get(i)) {
+        Some(hand) => hand,
+        None => return Ok(None),
+    };
+    let rank = hands.iter().filter(|other| *other < hand).count() + 1;
+
+    Ok(Some(format!(
+        "{} bid {}: {:?}, rank {} of {}, wins {}",
+        hand.cards.iter().map(T::value_char).collect::<String>(),
+        hand.point,
+        hand.hand_type,
+        rank,
+        hands.len(),
+        rank as u64 * u64::from(hand.point)
+    )))
+}
+
+fn query_hand(input: &[u8], args: &[&str]) -> std::result::Result<String, String> {
+    let line: usize = query::arg(args, 0, "LINE")?;
+    let first = describe_hand::<Card>(input, &Q1Strategy, line).map_err(|e| e.to_string())?;
+    let second =
+        describe_hand::<ModifiedCard>(input, &Q2Strategy, line).map_err(|e| e.to_string())?;
+
+    match (first, second) {
+        (Some(first), Some(second)) => Ok(format!("part 1: {}\npart 2: {}", first, second)),
+        _ => Err(format!("no hand on line {}", line)),
     }
+}
+
+pub fn part1(lines: Input) -> Result<Answer> {
+    part1_text(&collect_text(lines)?)
+}
 
-    println!("Sum: {:?}", sum);
+pub fn part1_text(text: &str) -> Result<Answer> {
+    run::<Card>(text, &Q1Strategy)
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
-    run::<Card>(lines, &Q1Strategy);
+pub fn part2(lines: Input) -> Result<Answer> {
+    part2_text(&collect_text(lines)?)
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    run::<ModifiedCard>(lines, &Q2Strategy);
+pub fn part2_text(text: &str) -> Result<Answer> {
+    run::<ModifiedCard>(text, &Q2Strategy)
+}
+
+/// Every hand with its bid and its kind under both rules.
+pub fn dump(lines: Input) -> Result<Value> {
+    let text = collect_text(lines)?;
+    let hands = load_hands::<Card>(&text, &Q1Strategy)?;
+    let jokers = load_hands::<ModifiedCard>(&text, &Q2Strategy)?;
+
+    let hands: Vec<Value> = hands
+        .iter()
+        .zip(&jokers)
+        .map(|(hand, joker)| {
+            json!({
+                "cards": hand.cards.iter().map(Card::value_char).collect::<String>(),
+                "bid": hand.point,
+                "type": format!("{:?}", hand.hand_type),
+                "joker_type": format!("{:?}", joker.hand_type),
+            })
+        })
+        .collect();
+
+    Ok(json!({ "hands": hands }))
+}
+
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let text = collect_text(lines)?;
+    let hands = load_hands::<Card>(&text, &Q1Strategy)?;
+    let jokers = load_hands::<ModifiedCard>(&text, &Q2Strategy)?;
+
+    Ok(vec![
+        ("hands", hands.len().to_string()),
+        ("types", type_histogram(&hands)),
+        ("types with jokers", type_histogram(&jokers)),
+    ])
+}
+
+fn type_histogram<T: CardTrait>(hands: &[Hand<T>]) -> String {
+    let kinds = [
+        HandType::FiveOfAKind,
+        HandType::FourOfAKind,
+        HandType::FullHouse,
+        HandType::ThreeOfAKind,
+        HandType::TwoPair,
+        HandType::OnePair,
+        HandType::HighCard,
+    ];
+    inspect::histogram(kinds.iter().map(|kind| {
+        let count = hands.iter().filter(|hand| hand.hand_type == *kind).count();
+        (format!("{:?}", kind), count)
+    }))
+}
+
+pub fn validate(lines: Input) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let (cards, bid) = match parse::pair(&line, ' ', i + 1) {
+            Ok(fields) => fields,
+            Err(e) => {
+                violations.push(Violation::from_error(e));
+                continue;
+            }
+        };
+
+        if cards.chars().count() != 5 {
+            violations.push(Violation::at(
+                i + 1,
+                format!("expected 5 cards, found {}", cards.chars().count()),
+            ));
+        }
+        if let Some(card) = cards.chars().find(|c| !"AKQJT98765432".contains(*c)) {
+            violations.push(Violation::at(i + 1, format!("invalid card {}", card)));
+        }
+        if bid.parse::<u32>().is_err() {
+            violations.push(Violation::at(i + 1, format!("invalid bid {}", bid)));
+        }
+    }
+
+    Ok(violations)
 }
@@ -1,51 +1,80 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Lines},
+use std::collections::HashMap;
+
+use serde_json::{json, Map as JsonMap, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::Stat,
+    math, parse,
+    query::Query,
+    read_input::{collect_text, input_text, Input},
+    validate::Violation,
 };
 
-enum Direction {
+pub const QUERIES: &[Query] = &[Query {
+    name: "path",
+    usage: "path NODE",
+    run: query_path,
+}];
+
+pub enum Direction {
     Left,
     Right,
 }
 
 impl Direction {
-    fn from_char(c: char) -> Direction {
+    fn from_char(c: char) -> Option<Direction> {
         match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction"),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
         }
     }
 }
 
+/// The network, borrowing its node names from the input text.
 #[derive(Debug)]
-struct Map {
-    map: HashMap<String, (String, String)>,
+pub struct Map<'a> {
+    map: HashMap<&'a str, (&'a str, &'a str)>,
 }
 
-impl Map {
-    fn new() -> Map {
+impl<'a> Map<'a> {
+    fn new() -> Map<'a> {
         Map {
             map: HashMap::new(),
         }
     }
 
-    fn add(&mut self, start: String, left: String, right: String) {
+    fn add(&mut self, start: &'a str, left: &'a str, right: &'a str) {
         self.map.insert(start, (left, right));
     }
+
+    fn get(&self, node: &str) -> Result<&(&'a str, &'a str)> {
+        self.map
+            .get(node)
+            .ok_or_else(|| Error::NoSolution(format!("node {} is not in the network", node)))
+    }
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
+pub fn load_network(text: &str) -> Result<(Vec<Direction>, Map<'_>)> {
     let mut directions: Vec<Direction> = Vec::new();
     let mut map = Map::new();
 
-    for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-
+    for (i, line) in text.lines().enumerate() {
         if i == 0 {
             for c in line.chars() {
-                directions.push(Direction::from_char(c));
+                let direction = Direction::from_char(c)
+                    .ok_or_else(|| Error::parse(1, format!("invalid direction {}", c)))?;
+                directions.push(direction);
             }
             continue;
         }
@@ -54,101 +83,102 @@ pub fn part1(lines: Lines<BufReader<File>>) {
             continue;
         }
 
-        let splits: Vec<&str> = line
-            .split_terminator(&['=', ',', '(', ')', ' '][..])
-            .filter(|x| !x.is_empty())
-            .collect();
+        let splits = parse::tokens(line, &['=', ',', '(', ')', ' ']);
+
+        if splits.len() != 3 {
+            return Err(Error::parse(i + 1, "expected `NODE = (LEFT, RIGHT)`"));
+        }
+
+        map.add(splits[0], splits[1], splits[2]);
+    }
 
-        map.add(
-            splits[0].to_string(),
-            splits[1].to_string(),
-            splits[2].to_string(),
-        );
+    if directions.is_empty() {
+        return Err(Error::parse(1, "missing directions"));
     }
 
-    let mut start_node = map.map.get("AAA").unwrap();
+    Ok((directions, map))
+}
+
+pub fn part1(lines: Input) -> Result<Answer> {
+    part1_text(&collect_text(lines)?)
+}
+
+pub fn part1_text(text: &str) -> Result<Answer> {
+    let (directions, map) = load_network(text)?;
+
+    // Once every (node, direction index) pair has been seen the walk repeats.
+    let limit = checked::mul(map.map.len(), directions.len(), "q08 step limit")?;
+    let mut start_node = map.get("AAA")?;
     let mut steps = 0;
     let mut step_out = false;
 
     while !step_out {
         for direction in &directions {
-            steps += 1;
+            cancel::check()?;
+            steps = checked::add(steps, 1, "q08 steps")?;
+            if steps > limit {
+                return Err(Error::NoSolution("ZZZ is unreachable".to_string()));
+            }
             match direction {
                 Direction::Left => {
                     if start_node.0 == "ZZZ" {
                         step_out = true;
                         break;
                     }
-                    start_node = map.map.get(&start_node.0).unwrap();
+                    start_node = map.get(start_node.0)?;
                 }
                 Direction::Right => {
                     if start_node.1 == "ZZZ" {
                         step_out = true;
                         break;
                     }
-                    start_node = map.map.get(&start_node.1).unwrap();
+                    start_node = map.get(start_node.1)?;
                 }
             }
         }
     }
 
-    println!("Steps: {}", steps);
+    Ok(steps.into())
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    let mut directions: Vec<Direction> = Vec::new();
-    let mut map = Map::new();
-    let mut starting_points: Vec<String> = Vec::new();
-
-    for (i, line) in lines.enumerate() {
-        let line = line.unwrap();
-
-        if i == 0 {
-            for c in line.chars() {
-                directions.push(Direction::from_char(c));
-            }
-            continue;
-        }
-
-        if line.is_empty() {
-            continue;
-        }
-
-        let splits: Vec<&str> = line
-            .split_terminator(&['=', ',', '(', ')', ' '][..])
-            .filter(|x| !x.is_empty())
-            .collect();
-
-        if splits[0].to_string().ends_with('A') {
-            starting_points.push(splits[0].to_string());
-        }
+pub fn part2(lines: Input) -> Result<Answer> {
+    part2_text(&collect_text(lines)?)
+}
 
-        map.add(
-            splits[0].to_string(),
-            splits[1].to_string(),
-            splits[2].to_string(),
-        );
+pub fn part2_text(text: &str) -> Result<Answer> {
+    let (directions, map) = load_network(text)?;
+    let starting_points: Vec<&str> = map.map.keys().copied().filter(|k| k.ends_with('A')).collect();
+    if starting_points.is_empty() {
+        return Err(Error::NoSolution("no starting nodes".to_string()));
     }
 
+    let limit = checked::mul(map.map.len(), directions.len(), "q08 step limit")? as u128;
     let mut counts: Vec<u128> = vec![0; starting_points.len()];
 
     for (i, starting_point) in starting_points.iter().enumerate() {
         let mut step_out = false;
-        let mut current_point = starting_point.clone();
+        let mut current_point = *starting_point;
 
         while !step_out {
             for direction in &directions {
-                counts[i] += 1;
+                cancel::check()?;
+                counts[i] = checked::add(counts[i], 1, "q08 ghost steps")?;
+                if counts[i] > limit {
+                    return Err(Error::NoSolution(format!(
+                        "no ..Z node reachable from {}",
+                        starting_point
+                    )));
+                }
                 match direction {
                     Direction::Left => {
-                        current_point = map.map.get(&current_point).unwrap().0.clone();
+                        current_point = map.get(current_point)?.0;
                         if current_point.ends_with('Z') {
                             step_out = true;
                             break;
                         }
                     }
                     Direction::Right => {
-                        current_point = map.map.get(&current_point).unwrap().1.clone();
+                        current_point = map.get(current_point)?.1;
                         if current_point.ends_with('Z') {
                             step_out = true;
                             break;
@@ -159,9 +189,178 @@ pub fn part2(lines: Lines<BufReader<File>>) {
         }
     }
 
-    println!("{}", lcm(&counts));
+    math::lcm_all(&counts)
+        .map(Answer::from)
+        .ok_or(Error::Overflow("q08 lcm"))
+}
+
+/// Steps until the walk from `start` first lands on a `..Z` node, and that node.
+fn steps_to_z<'a>(
+    map: &Map<'a>,
+    directions: &[Direction],
+    start: &'a str,
+    offset: usize,
+) -> Option<(usize, &'a str)> {
+    let limit = map.map.len() * directions.len();
+    let mut current = start;
+    for steps in 1..=limit {
+        let (left, right) = map.map.get(current)?;
+        current = match directions[(offset + steps - 1) % directions.len()] {
+            Direction::Left => *left,
+            Direction::Right => *right,
+        };
+        if current.ends_with('Z') {
+            return Some((steps, current));
+        }
+    }
+
+    None
+}
+
+/// How far the walk from a node gets before it first reaches a `..Z` node.
+fn query_path(input: &[u8], args: &[&str]) -> std::result::Result<String, String> {
+    let start = *args.first().ok_or("missing NODE")?;
+    let text = input_text(input).map_err(|e| e.to_string())?;
+    let (directions, map) = load_network(text).map_err(|e| e.to_string())?;
+    map.get(start).map_err(|e| e.to_string())?;
+
+    Ok(match steps_to_z(&map, &directions, start, 0) {
+        Some((steps, end)) => format!("{} reaches {} after {} steps", start, end, steps),
+        None => format!("no ..Z node is reachable from {}", start),
+    })
+}
+
+/// The directions and every node's left and right neighbours, by node name.
+pub fn dump(lines: Input) -> Result<Value> {
+    let text = collect_text(lines)?;
+    let (directions, map) = load_network(&text)?;
+    let nodes: JsonMap<String, Value> = map
+        .map
+        .iter()
+        .map(|(node, (left, right))| (node.to_string(), json!([left, right])))
+        .collect();
+
+    Ok(json!({
+        "directions": directions.iter().map(Direction::to_char).collect::<String>(),
+        "nodes": nodes,
+    }))
 }
 
-fn lcm(v: &Vec<u128>) -> u128 {
-    v.iter().fold(1, |a, &b| num::integer::lcm(a, b))
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let text = collect_text(lines)?;
+    let (directions, map) = load_network(&text)?;
+    let mut starts: Vec<&str> = map
+        .map
+        .keys()
+        .copied()
+        .filter(|k| k.ends_with('A'))
+        .collect();
+    starts.sort_unstable();
+    let ends = map.map.keys().filter(|k| k.ends_with('Z')).count();
+
+    Ok(vec![
+        ("directions", directions.len().to_string()),
+        ("nodes", map.map.len().to_string()),
+        (
+            "..A starts",
+            format!("{} ({})", starts.len(), starts.join(", ")),
+        ),
+        ("..Z ends", ends.to_string()),
+    ])
+}
+
+pub fn validate(lines: Input) -> Result<Vec<Violation>> {
+    let text = collect_text(lines)?;
+    let mut violations = Vec::new();
+    let mut directions: Vec<Direction> = Vec::new();
+    let mut map = Map::new();
+    let mut defined_at: HashMap<&str, usize> = HashMap::new();
+
+    for (i, line) in text.lines().enumerate() {
+        if i == 0 {
+            for c in line.chars() {
+                match Direction::from_char(c) {
+                    Some(direction) => directions.push(direction),
+                    None => violations.push(Violation::at(1, format!("invalid direction {}", c))),
+                }
+            }
+            if line.is_empty() {
+                violations.push(Violation::at(1, "missing directions"));
+            }
+            continue;
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let splits = parse::tokens(line, &['=', ',', '(', ')', ' ']);
+        if splits.len() != 3 {
+            violations.push(Violation::at(i + 1, "expected `NODE = (LEFT, RIGHT)`"));
+            continue;
+        }
+
+        if let Some(first) = defined_at.insert(splits[0], i + 1) {
+            violations.push(Violation::at(
+                i + 1,
+                format!("node {} was already defined on line {}", splits[0], first),
+            ));
+        }
+        map.add(splits[0], splits[1], splits[2]);
+    }
+
+    let mut nodes: Vec<(&&str, &usize)> = defined_at.iter().collect();
+    nodes.sort_by_key(|(_, line)| **line);
+
+    let mut undefined = false;
+    for (node, line) in &nodes {
+        let (left, right) = &map.map[*node];
+        for child in [left, right] {
+            if !map.map.contains_key(child) {
+                undefined = true;
+                violations.push(Violation::at(
+                    **line,
+                    format!("{} refers to undefined node {}", node, child),
+                ));
+            }
+        }
+    }
+
+    if !map.map.contains_key("AAA") || !map.map.contains_key("ZZZ") {
+        violations.push(Violation::global("part 1 needs both AAA and ZZZ"));
+    }
+
+    // following the ghosts needs every node defined and somewhere to go
+    if undefined || directions.is_empty() {
+        return Ok(violations);
+    }
+
+    // part 2 takes the LCM of the first arrival times, which is only right if
+    // every ghost then keeps returning to the same ..Z node with that period.
+    for (node, line) in nodes.iter().filter(|(node, _)| node.ends_with('A')) {
+        let (first, z) = match steps_to_z(&map, &directions, node, 0) {
+            Some(arrival) => arrival,
+            None => {
+                violations.push(Violation::at(**line, format!("no ..Z node reachable from {}", node)));
+                continue;
+            }
+        };
+
+        match steps_to_z(&map, &directions, z, first) {
+            Some((period, next_z)) if period == first && next_z == z => {}
+            Some((period, next_z)) => violations.push(Violation::at(
+                **line,
+                format!(
+                    "ghost from {} reaches {} after {} steps but {} after {} more, so LCM does not apply",
+                    node, z, first, next_z, period
+                ),
+            )),
+            None => violations.push(Violation::at(
+                **line,
+                format!("ghost from {} never returns to a ..Z node after {}", node, z),
+            )),
+        }
+    }
+
+    Ok(violations)
 }
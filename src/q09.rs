@@ -1,64 +1,90 @@
-use std::{
-    fs::File,
-    io::{BufReader, Lines},
-};
+use serde_json::{json, Value};
 
-pub fn part1(lines: Lines<BufReader<File>>) {
-    let mut sum = 0;
-    for line in lines {
-        let line = line.unwrap();
+use crate::{
+    answer::Answer,
+    cancel,
+    error::{Error, Result},
+    parse,
+    read_input::Input,
+};
 
-        let values = line
-            .split_whitespace()
-            .map(|x| x.parse::<i32>().unwrap())
-            .collect::<Vec<i32>>();
-        let val = recurse_values(values);
-        sum += val;
+pub fn load_histories(lines: Input) -> Result<Vec<Vec<i32>>> {
+    let mut histories = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        histories.push(parse::numbers::<i32>(&line, i + 1)?);
     }
 
-    println!("Sum: {}", sum);
+    Ok(histories)
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    let mut sum = 0;
-    for line in lines {
-        let line = line.unwrap();
-
-        let values = line
-            .split_whitespace()
-            .map(|x| x.parse::<i32>().unwrap())
-            .collect::<Vec<i32>>();
-        let val = recurse_values_part2(values);
-        sum += val;
+pub fn part1(lines: Input) -> Result<Answer> {
+    let mut sum: i64 = 0;
+    for (i, line) in lines.enumerate() {
+        let _history = crate::trace_span!("history", line = i + 1);
+        let val = recurse_values(parse::numbers::<i32>(&line?, i + 1)?)?;
+        crate::trace!("extrapolated", value = val);
+        sum = sum
+            .checked_add(i64::from(val))
+            .ok_or(Error::Overflow("q09 sum"))?;
     }
 
-    println!("Sum: {}", sum);
+    Ok(sum.into())
 }
 
-pub fn recurse_values(values: Vec<i32>) -> i32 {
-    if values.iter().all(|&x| x == 0) {
-        return 0;
+pub fn part2(lines: Input) -> Result<Answer> {
+    let mut sum: i64 = 0;
+    for (i, line) in lines.enumerate() {
+        let _history = crate::trace_span!("history", line = i + 1);
+        let val = recurse_values_part2(parse::numbers::<i32>(&line?, i + 1)?)?;
+        crate::trace!("extrapolated", value = val);
+        sum = sum
+            .checked_add(i64::from(val))
+            .ok_or(Error::Overflow("q09 sum"))?;
     }
 
+    Ok(sum.into())
+}
+
+fn differences(values: &[i32]) -> Result<Vec<i32>> {
     let mut new_values = Vec::new();
     for i in 1..values.len() {
-        new_values.push(values[i] - values[i - 1]);
+        cancel::check()?;
+        new_values.push(
+            values[i]
+                .checked_sub(values[i - 1])
+                .ok_or(Error::Overflow("q09 difference"))?,
+        );
     }
 
-    let val = recurse_values(new_values);
-    return val + values.last().unwrap();
+    Ok(new_values)
 }
 
-pub fn recurse_values_part2(values: Vec<i32>) -> i32 {
+pub fn recurse_values(values: Vec<i32>) -> Result<i32> {
+    let _row = crate::trace_span!("row", values = values.clone());
     if values.iter().all(|&x| x == 0) {
-        return 0;
+        return Ok(0);
     }
 
-    let mut new_values = Vec::new();
-    for i in 1..values.len() {
-        new_values.push(values[i] - values[i - 1]);
+    let val = recurse_values(differences(&values)?)?;
+    val.checked_add(*values.last().unwrap())
+        .ok_or(Error::Overflow("q09 extrapolation"))
+}
+
+pub fn recurse_values_part2(values: Vec<i32>) -> Result<i32> {
+    let _row = crate::trace_span!("row", values = values.clone());
+    if values.iter().all(|&x| x == 0) {
+        return Ok(0);
     }
 
-    let val = recurse_values_part2(new_values);
-    return values.first().unwrap() - val;
+    let val = recurse_values_part2(differences(&values)?)?;
+    values
+        .first()
+        .unwrap()
+        .checked_sub(val)
+        .ok_or(Error::Overflow("q09 extrapolation"))
+}
+
+pub fn dump(lines: Input) -> Result<Value> {
+    Ok(json!({ "histories": load_histories(lines)? }))
 }
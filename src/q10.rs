@@ -1,10 +1,25 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Lines},
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::Stat,
+    parse,
+    query::{self, Query},
+    read_input::{collect_text, input_text, Input},
+    validate::Violation,
 };
 
-struct Map {
+pub const QUERIES: &[Query] = &[Query {
+    name: "cell",
+    usage: "cell ROW COLUMN",
+    run: query_cell,
+}];
+
+pub struct Map {
     map: HashMap<(usize, usize), Pipe>,
     main_loop: HashMap<(usize, usize), Pipe>,
     current_pipe: Option<((usize, usize), Pipe)>,
@@ -30,24 +45,36 @@ impl Map {
         self.main_loop.insert(coordinates, pipe);
     }
 
-    fn traverse(&mut self) -> usize {
+    fn traverse(&mut self) -> Result<usize> {
+        if self.current_pipe.is_none() {
+            return Err(Error::NoSolution("missing start tile S".to_string()));
+        }
+
+        let mut started = false;
         for direction in [Direction::N, Direction::E, Direction::S, Direction::W].iter() {
             if self.move_next(*direction) {
+                started = true;
                 break;
             }
         }
+        if !started {
+            return Err(Error::NoSolution("no pipe connects to S".to_string()));
+        }
 
         let mut count = 1;
         while let Some((_, pipe)) = self.current_pipe {
+            cancel::check()?;
             if let Pipe::Start = pipe {
                 break;
             } else {
-                self.move_next(self.direction);
-                count += 1;
+                if !self.move_next(self.direction) || count > self.map.len() {
+                    return Err(Error::NoSolution("the loop through S is broken".to_string()));
+                }
+                count = checked::add(count, 1, "q10 loop length")?;
             }
         }
 
-        count
+        Ok(count)
     }
 
     fn move_next(&mut self, direction: Direction) -> bool {
@@ -56,7 +83,7 @@ impl Map {
         let coordinates: (usize, usize);
 
         match direction {
-            Direction::N => {
+            Direction::N if x > 0 => {
                 coordinates = (x - 1, y);
                 next_pipe = self.map.get(&coordinates);
             }
@@ -68,10 +95,11 @@ impl Map {
                 coordinates = (x + 1, y);
                 next_pipe = self.map.get(&coordinates);
             }
-            Direction::W => {
+            Direction::W if y > 0 => {
                 coordinates = (x, y - 1);
                 next_pipe = self.map.get(&coordinates);
             }
+            _ => return false,
         }
 
         if let Some(next_pipe) = next_pipe {
@@ -80,9 +108,7 @@ impl Map {
                     return false;
                 }
                 _ => {
-                    self.current_pipe = Some((coordinates, *next_pipe));
-                    self.main_loop.insert(coordinates, *next_pipe);
-                    self.direction = match (*next_pipe, direction) {
+                    let next_direction = match (*next_pipe, direction) {
                         (Pipe::NS, Direction::N) => Direction::N,
                         (Pipe::NS, Direction::S) => Direction::S,
                         (Pipe::SE, Direction::N) => Direction::E,
@@ -96,9 +122,13 @@ impl Map {
                         (Pipe::NW, Direction::S) => Direction::W,
                         (Pipe::NW, Direction::E) => Direction::N,
                         (Pipe::Start, _) => Direction::N,
-                        _ => panic!("Invalid pipe"),
+                        // the pipe has no opening facing us
+                        _ => return false,
                     };
 
+                    self.current_pipe = Some((coordinates, *next_pipe));
+                    self.main_loop.insert(coordinates, *next_pipe);
+                    self.direction = next_direction;
                     return true;
                 }
             }
@@ -106,79 +136,87 @@ impl Map {
         false
     }
 
-    fn load_map(lines: Lines<BufReader<File>>) -> Map {
+    pub fn load_map(text: &str) -> Result<Map> {
         let mut map = Map::new();
 
         let mut height = 0;
 
-        for (i, line) in lines.enumerate() {
-            let line = line.unwrap();
+        for (i, row) in parse::grid_text(text, Pipe::from_char)?.into_iter().enumerate() {
             if i == 0 {
-                map.width = line.len();
+                map.width = row.len();
             }
 
-            for (j, char) in line.chars().enumerate() {
-                let (coordinates, pipe) = match char {
-                    'S' => {
-                        let pipe = Pipe::Start;
-                        map.add_start((i, j), pipe);
-                        ((i, j), pipe)
-                    }
-                    '|' => ((i, j), Pipe::NS),
-                    '-' => ((i, j), Pipe::EW),
-                    'L' => ((i, j), Pipe::NE),
-                    'J' => ((i, j), Pipe::NW),
-                    '7' => ((i, j), Pipe::SW),
-                    'F' => ((i, j), Pipe::SE),
-                    '.' => ((i, j), Pipe::None),
-                    _ => {
-                        panic!("Invalid char");
-                    }
-                };
-                map.map.insert(coordinates, pipe);
+            for (j, pipe) in row.into_iter().enumerate() {
+                if let Pipe::Start = pipe {
+                    map.add_start((i, j), pipe);
+                }
+                map.map.insert((i, j), pipe);
             }
             height += 1;
         }
 
         map.height = height;
-        map
+        Ok(map)
     }
 
-    fn ray_beam_method(&mut self) -> usize {
-        self.traverse();
+    fn ray_beam_method(&mut self) -> Result<usize> {
+        self.traverse()?;
 
-        let mut crossed = 0;
         let mut count = 0;
-
         for i in 0..self.height {
             for j in 0..self.width {
-                if self.main_loop.get(&(i, j)).is_some() {
-                    continue;
+                cancel::check()?;
+                if self.enclosed((i, j)) {
+                    count = checked::add(count, 1, "q10 enclosed tiles")?;
                 }
+            }
+        }
 
-                // ray beams to the east
-                for z in j..self.width {
-                    if let Some(pipe) = self.main_loop.get(&(i, z)) {
-                        match pipe {
-                            Pipe::NS | Pipe::NW | Pipe::NE | Pipe::Start => {
-                                crossed += 1;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+        Ok(count)
+    }
 
-                if crossed % 2 == 1 {
-                    count += 1;
-                }
-                crossed = 0;
-            }
+    /// Whether a tile off the main loop lies inside it, by counting the loop
+    /// crossings of a ray beamed to the east. Needs `traverse` first.
+    fn enclosed(&self, (i, j): (usize, usize)) -> bool {
+        if self.main_loop.contains_key(&(i, j)) {
+            return false;
         }
 
-        count
+        let crossed = (j..self.width)
+            .filter(|z| {
+                matches!(
+                    self.main_loop.get(&(i, *z)),
+                    Some(Pipe::NS | Pipe::NW | Pipe::NE | Pipe::Start)
+                )
+            })
+            .count();
+        crossed % 2 == 1
     }
 }
 
+/// The tile at a 1-based row and column and where it sits relative to the loop.
+fn query_cell(input: &[u8], args: &[&str]) -> std::result::Result<String, String> {
+    let row: usize = query::arg(args, 0, "ROW")?;
+    let column: usize = query::arg(args, 1, "COLUMN")?;
+    let text = input_text(input).map_err(|e| e.to_string())?;
+    let mut map = Map::load_map(text).map_err(|e| e.to_string())?;
+    let position = (row.wrapping_sub(1), column.wrapping_sub(1));
+    let pipe = *map
+        .map
+        .get(&position)
+        .ok_or_else(|| format!("row {} column {} is outside the map", row, column))?;
+    map.traverse().map_err(|e| e.to_string())?;
+
+    let place = if map.main_loop.contains_key(&position) {
+        "on the main loop"
+    } else if map.enclosed(position) {
+        "enclosed by the main loop"
+    } else {
+        "outside the main loop"
+    };
+    Ok(format!("row {} column {}: {:?}, {}", row, column, pipe, place))
+}
+
 #[derive(Debug, Clone, Copy)]
 enum Pipe {
     NS,
@@ -191,6 +229,35 @@ enum Pipe {
     None,
 }
 
+impl Pipe {
+    fn from_char(c: char) -> Option<Pipe> {
+        match c {
+            'S' => Some(Pipe::Start),
+            '|' => Some(Pipe::NS),
+            '-' => Some(Pipe::EW),
+            'L' => Some(Pipe::NE),
+            'J' => Some(Pipe::NW),
+            '7' => Some(Pipe::SW),
+            'F' => Some(Pipe::SE),
+            '.' => Some(Pipe::None),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Pipe::Start => 'S',
+            Pipe::NS => '|',
+            Pipe::EW => '-',
+            Pipe::NE => 'L',
+            Pipe::NW => 'J',
+            Pipe::SW => '7',
+            Pipe::SE => 'F',
+            Pipe::None => '.',
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum Direction {
     N,
@@ -199,14 +266,136 @@ enum Direction {
     W,
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
-    let mut map = Map::load_map(lines);
-    let count = map.traverse();
-    println!("Part 1: {}", count / 2);
+pub fn part1(lines: Input) -> Result<Answer> {
+    part1_text(&collect_text(lines)?)
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    let mut map = Map::load_map(lines);
-    let count = map.ray_beam_method();
-    println!("Part 2: {}", count);
+pub fn part1_text(text: &str) -> Result<Answer> {
+    let mut map = Map::load_map(text)?;
+    let count = map.traverse()?;
+    Ok((count / 2).into())
+}
+
+pub fn part2(lines: Input) -> Result<Answer> {
+    part2_text(&collect_text(lines)?)
+}
+
+pub fn part2_text(text: &str) -> Result<Answer> {
+    let mut map = Map::load_map(text)?;
+    map.ray_beam_method().map(Answer::from)
+}
+
+/// The grid row by row and the main loop's tiles as 1-based `[row, column]`
+/// pairs in reading order; a broken loop is `null` with the reason beside it.
+pub fn dump(lines: Input) -> Result<Value> {
+    let text = collect_text(lines)?;
+    let mut map = Map::load_map(&text)?;
+    let grid: Vec<String> = (0..map.height)
+        .map(|i| {
+            (0..map.width)
+                .map(|j| map.map.get(&(i, j)).map_or(' ', |pipe| pipe.to_char()))
+                .collect()
+        })
+        .collect();
+    let start = map.current_pipe.map(|((i, j), _)| [i + 1, j + 1]);
+
+    let mut dump = json!({
+        "width": map.width,
+        "height": map.height,
+        "start": start,
+        "grid": grid,
+    });
+    match map.traverse() {
+        Ok(length) => {
+            let mut tiles: Vec<[usize; 2]> =
+                map.main_loop.keys().map(|&(i, j)| [i + 1, j + 1]).collect();
+            tiles.sort_unstable();
+            dump["loop_length"] = json!(length);
+            dump["main_loop"] = json!(tiles);
+        }
+        Err(e) => {
+            dump["main_loop"] = Value::Null;
+            dump["loop_error"] = json!(e.to_string());
+        }
+    }
+
+    Ok(dump)
+}
+
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let text = collect_text(lines)?;
+    let mut map = Map::load_map(&text)?;
+    let start = match map.current_pipe {
+        Some(((i, j), _)) => format!("row {} column {}", i + 1, j + 1),
+        None => "none".to_string(),
+    };
+    let length = match map.traverse() {
+        Ok(length) => format!("{} (farthest tile {} steps away)", length, length / 2),
+        Err(e) => e.to_string(),
+    };
+
+    Ok(vec![
+        ("grid", format!("{} x {}", map.height, map.width)),
+        ("start", start),
+        ("loop length", length),
+    ])
+}
+
+pub fn validate(lines: Input) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    let mut grid: Vec<Vec<char>> = Vec::new();
+    let mut starts = Vec::new();
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        for (j, c) in line.chars().enumerate() {
+            match Pipe::from_char(c) {
+                Some(Pipe::Start) => starts.push((i, j)),
+                Some(_) => {}
+                None => violations.push(Violation::at(
+                    i + 1,
+                    format!("invalid tile {} in column {}", c, j + 1),
+                )),
+            }
+        }
+        grid.push(line.chars().collect());
+    }
+
+    let tile = |i: Option<usize>, j: Option<usize>| -> char {
+        match (i, j) {
+            (Some(i), Some(j)) => grid.get(i).and_then(|row| row.get(j)).copied().unwrap_or('.'),
+            _ => '.',
+        }
+    };
+
+    match starts[..] {
+        [] => violations.push(Violation::global("missing start tile S")),
+        [(i, j)] => {
+            let connected = [
+                "|7F".contains(tile(i.checked_sub(1), Some(j))),
+                "|LJ".contains(tile(Some(i + 1), Some(j))),
+                "-LF".contains(tile(Some(i), j.checked_sub(1))),
+                "-J7".contains(tile(Some(i), Some(j + 1))),
+            ]
+            .iter()
+            .filter(|c| **c)
+            .count();
+            if connected != 2 {
+                violations.push(Violation::at(
+                    i + 1,
+                    format!("S connects to {} pipes, expected 2", connected),
+                ));
+            }
+        }
+        _ => {
+            for (i, j) in &starts {
+                violations.push(Violation::at(
+                    i + 1,
+                    format!("one of {} start tiles, in column {}", starts.len(), j + 1),
+                ));
+            }
+        }
+    }
+
+    Ok(violations)
 }
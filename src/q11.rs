@@ -1,11 +1,19 @@
 use core::fmt;
-use std::{
-    fmt::{Display, Formatter},
-    fs::File,
-    io::{BufReader, Lines},
+use std::fmt::{Display, Formatter};
+
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::Stat,
+    parse,
+    read_input::Input,
+    validate::Violation,
 };
 
-struct Universe {
+pub struct Universe {
     grid: Vec<Vec<char>>,
     galaxies: Vec<(usize, usize)>,
 }
@@ -85,64 +93,168 @@ impl Universe {
     }
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
+pub fn load_universe(lines: Input) -> Result<Universe> {
     let mut universe = Universe::new();
 
-    for line in lines {
-        let line = line.unwrap();
-        let mut row = Vec::new();
-        line.chars().for_each(|c| row.push(c));
+    for row in parse::grid(lines, |c| matches!(c, '.' | '#').then_some(c))? {
         universe.add_row(row);
     }
 
-    universe.expand_universe(0);
-    universe.insert_galaxies();
+    if universe.grid.is_empty() {
+        return Err(Error::parse(1, "empty universe"));
+    }
+
+    Ok(universe)
+}
+
+/// Galaxies per row and per column, which is all the distances depend on.
+struct GalaxyCounts {
+    rows: Vec<usize>,
+    columns: Vec<usize>,
+}
 
-    let mut total_distance = 0;
+/// Reads the universe a row at a time, so the grid itself is never stored.
+fn count_galaxies(lines: Input) -> Result<GalaxyCounts> {
+    let mut rows = Vec::new();
+    let mut columns: Vec<usize> = Vec::new();
 
-    for i in 0..universe.galaxies.len() {
-        for j in i + 1..universe.galaxies.len() {
-            total_distance += universe.distance_between_galaxies(i, j);
+    parse::grid_rows(lines, |c| matches!(c, '.' | '#').then_some(c == '#'), |row| {
+        cancel::check()?;
+        if columns.is_empty() {
+            columns = vec![0; row.len()];
         }
+        for (column, _) in row.iter().enumerate().filter(|(_, galaxy)| **galaxy) {
+            columns[column] = checked::add(columns[column], 1, "q11 galaxies per column")?;
+        }
+        rows.push(row.iter().filter(|galaxy| **galaxy).count());
+        Ok(())
+    })?;
+
+    if rows.is_empty() {
+        return Err(Error::parse(1, "empty universe"));
     }
 
-    println!("Total distance: {}", total_distance);
+    Ok(GalaxyCounts { rows, columns })
 }
 
-pub fn part2(lines: Lines<BufReader<File>>) {
-    let mut universe = Universe::new();
+/// Sum over all pairs of galaxies of their distance along one axis, where
+/// every empty line counts as `1 + extra` lines.
+fn axis_distances(counts: &[usize], extra: usize) -> Result<usize> {
+    let overflow = || Error::Overflow("q11 distance");
+    let mut position: usize = 0;
+    let mut seen: usize = 0;
+    let mut position_sum: usize = 0;
+    let mut total: usize = 0;
 
-    for line in lines {
-        let line = line.unwrap();
-        let mut row = Vec::new();
-        line.chars().for_each(|c| row.push(c));
-        universe.add_row(row);
+    for &count in counts {
+        cancel::check()?;
+        if count == 0 {
+            position = position.checked_add(extra).ok_or_else(overflow)?;
+        } else {
+            // each of these galaxies is `position - p` away from every earlier galaxy at p
+            let gaps = position
+                .checked_mul(seen)
+                .and_then(|ahead| ahead.checked_sub(position_sum))
+                .and_then(|gaps| gaps.checked_mul(count))
+                .ok_or_else(overflow)?;
+            total = total.checked_add(gaps).ok_or_else(overflow)?;
+            seen = checked::add(seen, count, "q11 galaxies seen")?;
+            position_sum = position
+                .checked_mul(count)
+                .and_then(|sum| sum.checked_add(position_sum))
+                .ok_or_else(overflow)?;
+        }
+        position = position.checked_add(1).ok_or_else(overflow)?;
     }
 
-    universe.expand_universe(0);
-    universe.insert_galaxies();
+    Ok(total)
+}
 
-    let mut first_iter = 0;
+fn total_distance(counts: &GalaxyCounts, extra: usize) -> Result<usize> {
+    axis_distances(&counts.rows, extra)?
+        .checked_add(axis_distances(&counts.columns, extra)?)
+        .ok_or(Error::Overflow("q11 distance"))
+}
 
-    for i in 0..universe.galaxies.len() {
-        for j in i + 1..universe.galaxies.len() {
-            first_iter += universe.distance_between_galaxies(i, j);
-        }
-    }
+pub fn part1(lines: Input) -> Result<Answer> {
+    total_distance(&count_galaxies(lines)?, 0).map(Answer::from)
+}
+
+pub fn part2(lines: Input) -> Result<Answer> {
+    total_distance(&count_galaxies(lines)?, 999_999).map(Answer::from)
+}
 
-    universe.expand_universe(1);
+/// The galaxies as 1-based `[row, column]` pairs in reading order, and the
+/// empty rows and columns that expand.
+pub fn dump(lines: Input) -> Result<Value> {
+    let mut universe = load_universe(lines)?;
     universe.insert_galaxies();
+    let grid = &universe.grid;
+    let width = grid[0].len();
+    let empty_rows: Vec<usize> = (0..grid.len())
+        .filter(|&i| grid[i].iter().all(|c| *c == '.'))
+        .map(|i| i + 1)
+        .collect();
+    let empty_columns: Vec<usize> = (0..width)
+        .filter(|&j| grid.iter().all(|row| row[j] == '.'))
+        .map(|j| j + 1)
+        .collect();
+    let galaxies: Vec<[usize; 2]> = universe
+        .galaxies
+        .iter()
+        .map(|&(i, j)| [i + 1, j + 1])
+        .collect();
 
-    let mut second_iter = 0;
+    Ok(json!({
+        "width": width,
+        "height": grid.len(),
+        "galaxies": galaxies,
+        "empty_rows": empty_rows,
+        "empty_columns": empty_columns,
+    }))
+}
 
-    for i in 0..universe.galaxies.len() {
-        for j in i + 1..universe.galaxies.len() {
-            second_iter += universe.distance_between_galaxies(i, j);
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let counts = count_galaxies(lines)?;
+    let empty = |counts: &[usize]| counts.iter().filter(|count| **count == 0).count();
+
+    Ok(vec![
+        ("galaxies", counts.rows.iter().sum::<usize>().to_string()),
+        (
+            "empty rows",
+            format!("{} of {}", empty(&counts.rows), counts.rows.len()),
+        ),
+        (
+            "empty columns",
+            format!("{} of {}", empty(&counts.columns), counts.columns.len()),
+        ),
+    ])
+}
+
+pub fn validate(lines: Input) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    let mut width = None;
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let row_width = line.chars().count();
+        match width {
+            None => width = Some(row_width),
+            Some(width) if width != row_width => violations.push(Violation::at(
+                i + 1,
+                format!("row is {} wide, expected {}", row_width, width),
+            )),
+            _ => {}
+        }
+
+        if let Some(c) = line.chars().find(|c| *c != '.' && *c != '#') {
+            violations.push(Violation::at(i + 1, format!("invalid cell {}", c)));
         }
     }
 
-    let diff =  second_iter - first_iter;
+    if width.is_none() {
+        violations.push(Violation::global("empty universe"));
+    }
 
-    let total_distance = 999_999 * diff + first_iter;
-    println!("Total distance: {}", total_distance);
+    Ok(violations)
 }
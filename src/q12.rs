@@ -1,81 +1,87 @@
-use std::{
-    fmt::Display,
-    fs::File,
-    io::{BufReader, Lines},
+use std::fmt::Display;
+
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    cancel, checked,
+    error::{Error, Result},
+    inspect::{self, Stat},
+    parse,
+    read_input::Input,
 };
 
 #[derive(Clone)]
-struct Springs {
+pub struct Springs {
     state: Vec<Vec<State>>,
     broken: Vec<Vec<usize>>,
 }
 
-impl Springs {
-    fn permute_by_index(self, index: usize) -> Vec<Vec<State>> {
-        let mut new_states = vec![self.state[index].clone()];
-        let mut new_states_out: Vec<Vec<State>> = vec![];
-
-        while new_states
-            .iter()
-            .any(|s| s.iter().any(|s| *s == State::QuestionMark))
-        {
-            if let Some(mut new_state) = new_states.pop() {
-                if new_state.iter().all(|s| *s != State::QuestionMark) {
-                    new_states_out.push(new_state.clone());
-                    continue;
-                }
+fn permutations(states: &[State]) -> Result<Vec<Vec<State>>> {
+    let mut new_states = vec![states.to_vec()];
+    let mut new_states_out: Vec<Vec<State>> = vec![];
+
+    while new_states
+        .iter()
+        .any(|s| s.contains(&State::QuestionMark))
+    {
+        cancel::check()?;
+        if let Some(mut new_state) = new_states.pop() {
+            if new_state.iter().all(|s| *s != State::QuestionMark) {
+                new_states_out.push(new_state.clone());
+                continue;
+            }
 
-                for i in 0..new_state.len() {
-                    if new_state[i] == State::QuestionMark {
-                        new_state[i] = State::Dot;
-                        new_states.push(new_state.clone());
-                        new_state[i] = State::Pound;
-                        new_states.push(new_state.clone());
-                        break;
-                    }
+            for i in 0..new_state.len() {
+                if new_state[i] == State::QuestionMark {
+                    new_state[i] = State::Dot;
+                    new_states.push(new_state.clone());
+                    new_state[i] = State::Pound;
+                    new_states.push(new_state.clone());
+                    break;
                 }
             }
         }
+    }
 
-        new_states_out.append(&mut new_states);
+    new_states_out.append(&mut new_states);
 
-        new_states_out
-    }
+    Ok(new_states_out)
+}
 
-    fn count(&self, index: usize) -> usize {
-        let permutated_states = self.clone().permute_by_index(index);
-        let mut total_count = 0;
-
-        for states in permutated_states {
-            let mut counts = Vec::new();
-            let mut count = 0;
-
-            for state in &states {
-                match state {
-                    State::Pound => count += 1,
-                    State::Dot => {
-                        if count > 0 {
-                            counts.push(count);
-                        }
-                        count = 0;
-                    }
-                    _ => {
-                        panic!("There should be no question marks");
+/// Number of ways to fill in the `?`s so the runs of `#` match `broken`.
+fn arrangements(states: &[State], broken: &[usize]) -> Result<usize> {
+    let mut total_count = 0;
+
+    for states in permutations(states)? {
+        let mut counts = Vec::new();
+        let mut count = 0;
+
+        for state in &states {
+            match state {
+                State::Pound => count = checked::add(count, 1, "q12 group length")?,
+                State::Dot => {
+                    if count > 0 {
+                        counts.push(count);
                     }
+                    count = 0;
+                }
+                _ => {
+                    panic!("There should be no question marks");
                 }
             }
+        }
 
-            if count > 0 {
-                counts.push(count);
-            }
-
-            if counts == self.broken[index] {
-                total_count += 1;
-            }
+        if count > 0 {
+            counts.push(count);
         }
 
-        total_count
+        if counts == broken {
+            total_count = checked::add(total_count, 1, "q12 arrangements")?;
+        }
     }
+
+    Ok(total_count)
 }
 
 impl Display for Springs {
@@ -104,12 +110,12 @@ enum State {
 }
 
 impl State {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '?' => State::QuestionMark,
-            '.' => State::Dot,
-            '#' => State::Pound,
-            _ => panic!("Unknown state"),
+            '?' => Some(State::QuestionMark),
+            '.' => Some(State::Dot),
+            '#' => Some(State::Pound),
+            _ => None,
         }
     }
 }
@@ -139,22 +145,74 @@ impl Springs {
     }
 }
 
-pub fn part1(lines: Lines<BufReader<File>>) {
+fn parse_row(line: &str, line_number: usize) -> Result<(Vec<State>, Vec<usize>)> {
+    let (first, second) = parse::pair(line, ' ', line_number)?;
+    let states = first
+        .chars()
+        .map(State::from_char)
+        .collect::<Option<_>>()
+        .ok_or_else(|| Error::parse(line_number, "unknown spring state"))?;
+    let broken: Vec<usize> = parse::delimited(second, ',', line_number)?;
+
+    Ok((states, broken))
+}
+
+pub fn load_springs(lines: Input) -> Result<Springs> {
     let mut springs = Springs::new();
-    for line in lines {
-        let line = line.unwrap();
-        let (first, second) = line.split_once(' ').unwrap();
-        let states = first.chars().map(State::from_char).collect();
-        let broken: Vec<usize> = second.split(',').map(|s| s.parse().unwrap()).collect();
+    for (i, line) in lines.enumerate() {
+        let (states, broken) = parse_row(&line?, i + 1)?;
         springs.add_line(states, broken);
     }
 
-    let mut sum = 0;
+    Ok(springs)
+}
+
+pub fn part1(lines: Input) -> Result<Answer> {
+    let mut sum: usize = 0;
 
-    for i in 0..springs.state.len() {
-        sum += springs.count(i);
+    for (i, line) in lines.enumerate() {
+        let (states, broken) = parse_row(&line?, i + 1)?;
+        sum = checked::add(sum, arrangements(&states, &broken)?, "q12 arrangement sum")?;
     }
-    println!("sum: {}", sum);
+    Ok(sum.into())
+}
 
+/// Every row's springs and its runs of broken ones.
+pub fn dump(lines: Input) -> Result<Value> {
+    let springs = load_springs(lines)?;
+    let records: Vec<Value> = springs
+        .state
+        .iter()
+        .zip(&springs.broken)
+        .map(|(states, broken)| {
+            json!({
+                "springs": states.iter().map(State::to_string).collect::<String>(),
+                "broken": broken,
+            })
+        })
+        .collect();
+
+    Ok(json!({ "records": records }))
+}
 
+/// How many `?`s the rows have: part 1 tries every way to fill them in, so
+/// the largest count decides how long it takes.
+pub fn inspect(lines: Input) -> Result<Vec<Stat>> {
+    let springs = load_springs(lines)?;
+    let unknowns: Vec<usize> = springs
+        .state
+        .iter()
+        .map(|states| states.iter().filter(|s| **s == State::QuestionMark).count())
+        .collect();
+    let most = unknowns.iter().copied().max().unwrap_or(0);
+    let per_row = (0..=most).filter_map(|n| {
+        let rows = unknowns.iter().filter(|count| **count == n).count();
+        (rows > 0).then_some((n, rows))
+    });
+
+    Ok(vec![
+        ("records", unknowns.len().to_string()),
+        ("question marks", unknowns.iter().sum::<usize>().to_string()),
+        ("rows by question mark count", inspect::histogram(per_row)),
+    ])
 }
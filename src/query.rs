@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use crate::{q07, q08, q10};
+
+/// A day specific command, run against the raw input with the words after
+/// its name.
+pub struct Query {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub run: fn(&[u8], &[&str]) -> Result<String, String>,
+}
+
+pub fn queries(day: u8) -> &'static [Query] {
+    match day {
+        7 => q07::QUERIES,
+        8 => q08::QUERIES,
+        10 => q10::QUERIES,
+        _ => &[],
+    }
+}
+
+/// Parses the query argument at `index`, naming it as `name` in errors.
+pub fn arg<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let value = args.get(index).ok_or_else(|| format!("missing {}", name))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid {}: {}", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(day: u8, name: &str, input: &str, args: &[&str]) -> Result<String, String> {
+        let query = queries(day)
+            .iter()
+            .find(|query| query.name == name)
+            .unwrap();
+        (query.run)(input.as_bytes(), args)
+    }
+
+    #[test]
+    fn parses_arguments_by_position() {
+        assert_eq!(arg::<usize>(&["3", "x"], 0, "ROW"), Ok(3));
+        assert_eq!(
+            arg::<usize>(&["3"], 1, "COLUMN"),
+            Err("missing COLUMN".to_string())
+        );
+        assert_eq!(
+            arg::<usize>(&["3", "x"], 1, "COLUMN"),
+            Err("invalid COLUMN: x".to_string())
+        );
+        assert_eq!(
+            arg::<usize>(&["-1"], 0, "LINE"),
+            Err("invalid LINE: -1".to_string())
+        );
+    }
+
+    #[test]
+    fn only_some_days_have_queries() {
+        let names = |day| {
+            queries(day)
+                .iter()
+                .map(|query| query.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(7), ["hand"]);
+        assert_eq!(names(8), ["path"]);
+        assert_eq!(names(10), ["cell"]);
+        assert!(names(1).is_empty());
+    }
+
+    #[test]
+    fn answers_well_formed_queries() {
+        let network = "LR\n\nAAA = (BBZ, XXX)\nBBZ = (AAA, XXX)\nXXX = (XXX, XXX)\n";
+        let pipes = ".....\n.S-7.\n.|.|.\n.L-J.\n.....\n";
+
+        assert_eq!(
+            run(8, "path", network, &["AAA"]),
+            Ok("AAA reaches BBZ after 1 steps".to_string())
+        );
+        assert_eq!(
+            run(10, "cell", pipes, &["2", "3"]),
+            Ok("row 2 column 3: EW, on the main loop".to_string())
+        );
+        assert_eq!(
+            run(10, "cell", pipes, &["3", "3"]),
+            Ok("row 3 column 3: None, enclosed by the main loop".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_queries() {
+        let pipes = ".....\n.S-7.\n.|.|.\n.L-J.\n.....\n";
+
+        assert_eq!(
+            run(7, "hand", "32T3K 765\n", &[]),
+            Err("missing LINE".to_string())
+        );
+        assert_eq!(
+            run(7, "hand", "32T3K 765\n", &["2"]),
+            Err("no hand on line 2".to_string())
+        );
+        assert_eq!(run(8, "path", "LR\n", &[]), Err("missing NODE".to_string()));
+        assert_eq!(
+            run(10, "cell", pipes, &["2"]),
+            Err("missing COLUMN".to_string())
+        );
+        assert_eq!(
+            run(10, "cell", pipes, &["0", "1"]),
+            Err("row 0 column 1 is outside the map".to_string())
+        );
+    }
+}
@@ -1,10 +1,112 @@
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Lines},
+    io::{self, BufRead, BufReader, Cursor, Lines, Read},
+    ops::Deref,
+    str,
 };
 
-pub fn read_input_lines(path: &str) -> Lines<BufReader<File>> {
-    let file = File::open(path).unwrap();
-    let buf_reader = BufReader::new(file);
+use memmap2::Mmap;
+
+use crate::error::{Error, Result};
+
+pub type Input = Lines<Box<dyn BufRead>>;
+
+/// A whole input file, memory-mapped when possible. Only solvers with a text
+/// entry point borrow from it; the rest still get a copy as owned lines.
+pub enum InputBuffer {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for InputBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputBuffer::Owned(bytes) => bytes,
+            InputBuffer::Mapped(map) => map,
+        }
+    }
+}
+
+/// Maps `path` into memory, falling back to reading it for empty files and
+/// anything that can't be mapped, like pipes.
+pub fn map_input(path: &str) -> io::Result<InputBuffer> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() > 0 {
+        // Safety: inputs aren't expected to change while a run reads them; if
+        // one is truncated underneath us the process may crash, as with any
+        // memory-mapped file.
+        if let Ok(map) = unsafe { Mmap::map(&file) } {
+            return Ok(InputBuffer::Mapped(map));
+        }
+    }
+
+    read_input_bytes(path).map(InputBuffer::Owned)
+}
+
+/// The input as text, for solvers that parse borrowed slices of it.
+pub fn input_text(bytes: &[u8]) -> Result<&str> {
+    str::from_utf8(bytes).map_err(|e| {
+        let line = bytes[..e.valid_up_to()].iter().filter(|b| **b == b'\n').count() + 1;
+        Error::parse(line, "input is not valid UTF-8")
+    })
+}
+
+/// Joins streamed lines back into one text, so the `Input` entry points of
+/// text based solvers can share their parsers.
+pub fn collect_text(lines: Input) -> io::Result<String> {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(&line?);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+pub fn read_input_lines(path: &str) -> io::Result<Input> {
+    let file = File::open(path)?;
+    let buf_reader: Box<dyn BufRead> = Box::new(BufReader::new(file));
+    Ok(buf_reader.lines())
+}
+
+pub fn read_input_bytes(path: &str) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn input_from_bytes(bytes: Vec<u8>) -> Input {
+    let buf_reader: Box<dyn BufRead> = Box::new(Cursor::new(bytes));
     buf_reader.lines()
 }
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// FNV-1a hash of the raw input, stable across runs and toolchains.
+pub fn hash_input(bytes: &[u8]) -> u64 {
+    fnv1a(FNV_OFFSET, bytes)
+}
+
+/// Same as `hash_input`, reading the file in chunks instead of all at once.
+pub fn hash_file(path: &str) -> io::Result<u64> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hash = FNV_OFFSET;
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(hash);
+        }
+        hash = fnv1a(hash, chunk);
+        let len = chunk.len();
+        reader.consume(len);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    answer::Answer,
+    cache::CACHE_PATH,
+    tsv::{Record, Table},
+};
+
+pub const ANSWERS_PATH: &str = ".aoc/answers.tsv";
+pub const LAST_RUN_PATH: &str = ".aoc/last_run.tsv";
+pub const BENCH_PATH: &str = ".aoc/bench.tsv";
+/// Benchmark medians later runs are checked against.
+pub const BASELINE_PATH: &str = ".aoc/baseline.tsv";
+pub const HISTORY_PATH: &str = ".aoc/history.tsv";
+/// External solvers for `compare`, one `name<TAB>day<TAB>command` per line.
+pub const COMPARE_PATH: &str = ".aoc/compare.tsv";
+/// One report file per caught solver panic.
+pub const CRASH_DIR: &str = ".aoc/crashes";
+
+/// Where `run` keeps its records: the paths above by default, somewhere
+/// else for tests so they leave the real records alone.
+#[derive(Debug, Clone)]
+pub struct RecordPaths {
+    pub cache: String,
+    pub answers: String,
+    pub last_run: String,
+    pub history: String,
+    pub crashes: String,
+}
+
+impl RecordPaths {
+    /// The same file names, under `dir` instead of `.aoc`.
+    pub fn under(dir: impl AsRef<Path>) -> RecordPaths {
+        let path = |default: &str| {
+            let name = Path::new(default).file_name().unwrap_or_default();
+            dir.as_ref().join(name).to_string_lossy().into_owned()
+        };
+
+        RecordPaths {
+            cache: path(CACHE_PATH),
+            answers: path(ANSWERS_PATH),
+            last_run: path(LAST_RUN_PATH),
+            history: path(HISTORY_PATH),
+            crashes: path(CRASH_DIR),
+        }
+    }
+}
+
+impl Default for RecordPaths {
+    fn default() -> Self {
+        RecordPaths {
+            cache: CACHE_PATH.to_string(),
+            answers: ANSWERS_PATH.to_string(),
+            last_run: LAST_RUN_PATH.to_string(),
+            history: HISTORY_PATH.to_string(),
+            crashes: CRASH_DIR.to_string(),
+        }
+    }
+}
+
+/// An empty directory of the test's own under the system's temporary
+/// directory, for records that must not land in the real `.aoc`.
+#[cfg(test)]
+pub fn scratch_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Answers confirmed correct, used to mark runs as verified.
+pub type Answers = Table<VerifiedAnswer>;
+
+/// An accepted answer, with the input it was accepted for so runs on other
+/// inputs are neither verified nor flagged as wrong.
+#[derive(Debug, Clone)]
+pub struct VerifiedAnswer {
+    pub input_hash: u64,
+    pub answer: Answer,
+}
+
+impl VerifiedAnswer {
+    /// The answer expected for `input_hash`, if it is the accepted input.
+    pub fn for_input(&self, input_hash: u64) -> Option<&Answer> {
+        (self.input_hash == input_hash).then_some(&self.answer)
+    }
+}
+
+impl Record for VerifiedAnswer {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [input_hash, answer] => Some(VerifiedAnswer {
+                input_hash: u64::from_str_radix(input_hash, 16).ok()?,
+                answer: Answer::parse(answer),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![format!("{:016x}", self.input_hash), self.answer.to_string()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LastRun {
+    /// Unix seconds.
+    pub timestamp: u64,
+    pub input_hash: u64,
+    pub elapsed: Duration,
+    pub answer: Answer,
+}
+
+impl Record for LastRun {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [timestamp, input_hash, elapsed, answer] => Some(LastRun {
+                timestamp: timestamp.parse().ok()?,
+                input_hash: u64::from_str_radix(input_hash, 16).ok()?,
+                elapsed: Duration::from_nanos(elapsed.parse().ok()?),
+                answer: Answer::parse(answer),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            format!("{:016x}", self.input_hash),
+            self.elapsed.as_nanos().to_string(),
+            self.answer.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub timestamp: u64,
+    pub iterations: u32,
+    pub median: Duration,
+}
+
+impl Record for BenchResult {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [timestamp, iterations, median] => Some(BenchResult {
+                timestamp: timestamp.parse().ok()?,
+                iterations: iterations.parse().ok()?,
+                median: Duration::from_nanos(median.parse().ok()?),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.iterations.to_string(),
+            self.median.as_nanos().to_string(),
+        ]
+    }
+}
+
+/// A saved benchmark, with the input it was measured on so a check on a
+/// different input isn't mistaken for a regression.
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    pub timestamp: u64,
+    pub input_hash: u64,
+    pub iterations: u32,
+    pub median: Duration,
+}
+
+impl Record for Baseline {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [timestamp, input_hash, iterations, median] => Some(Baseline {
+                timestamp: timestamp.parse().ok()?,
+                input_hash: u64::from_str_radix(input_hash, 16).ok()?,
+                iterations: iterations.parse().ok()?,
+                median: Duration::from_nanos(median.parse().ok()?),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            format!("{:016x}", self.input_hash),
+            self.iterations.to_string(),
+            self.median.as_nanos().to_string(),
+        ]
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,294 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    inspect,
+    query::{arg, queries},
+    read_input::{hash_input, input_from_bytes, read_input_bytes},
+    runner::{run_solution, RunOptions},
+    solutions,
+};
+
+/// Lines `show` prints when not told how many.
+const SHOW_LINES: usize = 20;
+
+struct Session {
+    day: u8,
+    input_path: String,
+    input: Vec<u8>,
+    history: Vec<String>,
+}
+
+impl Session {
+    fn reload(&mut self) -> Result<String, String> {
+        let input = read_input_bytes(&self.input_path)
+            .map_err(|e| format!("{}: {}", self.input_path, e))?;
+        let changed = hash_input(&input) != hash_input(&self.input);
+        self.input = input;
+
+        Ok(format!(
+            "reloaded {} ({} bytes{})",
+            self.input_path,
+            self.input.len(),
+            if changed { ", changed" } else { ", unchanged" }
+        ))
+    }
+
+    fn part(&self, part: u8) -> Result<String, String> {
+        let solution = solutions::find(self.day, part)
+            .ok_or_else(|| format!("no solution registered for day {} part {}", self.day, part))?;
+        let options = RunOptions {
+            no_cache: true,
+            ..RunOptions::default()
+        };
+        let result =
+            run_solution(solution, &self.input, None, &options).map_err(|e| e.to_string())?;
+
+        Ok(format!("{} in {:?}", result.answer, result.elapsed))
+    }
+
+    /// Input lines `from..from + count`, numbered from 1.
+    fn show(&self, args: &[&str]) -> Result<String, String> {
+        let from: usize = if args.is_empty() {
+            1
+        } else {
+            arg(args, 0, "FROM")?
+        };
+        let count = if args.len() < 2 {
+            SHOW_LINES
+        } else {
+            arg(args, 1, "COUNT")?
+        };
+        let text = String::from_utf8_lossy(&self.input);
+
+        Ok(text
+            .lines()
+            .enumerate()
+            .skip(from.saturating_sub(1))
+            .take(count)
+            .map(|(i, line)| format!("{:>5}  {}", i + 1, line))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn stats(&self) -> String {
+        let mut stats = inspect::general(&self.input);
+        if let Some(inspector) = inspect::inspector(self.day) {
+            match inspector(input_from_bytes(self.input.clone())) {
+                Ok(day_stats) => stats.extend(day_stats),
+                Err(e) => stats.push(("error", e.to_string())),
+            }
+        }
+        inspect::to_text(&stats)
+    }
+
+    fn help(&self) -> String {
+        let mut help = vec![
+            "part1, part2        solve a part on the loaded input".to_string(),
+            "show [FROM [COUNT]] print input lines".to_string(),
+            "stats               summarise the input".to_string(),
+            "reload              read the input file again".to_string(),
+            "history             list this session's commands; !N or !! repeats one".to_string(),
+            "quit".to_string(),
+        ];
+        for query in queries(self.day) {
+            help.push(format!("{:<20}day {} query", query.usage, self.day));
+        }
+        help.join("\n")
+    }
+
+    /// Runs one command line; `None` means the session is over.
+    fn execute(&mut self, line: &str) -> Option<Result<String, String>> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Some(Ok(String::new())),
+        };
+
+        let output = match command {
+            "quit" | "exit" => return None,
+            "help" => Ok(self.help()),
+            "part1" => self.part(1),
+            "part2" => self.part(2),
+            "show" => self.show(args),
+            "stats" => Ok(self.stats()),
+            "reload" => self.reload(),
+            "history" => Ok(self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>4}  {}", i + 1, line))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            _ => match queries(self.day).iter().find(|query| query.name == command) {
+                Some(query) => (query.run)(&self.input, args)
+                    .map_err(|e| format!("{} (usage: {})", e, query.usage)),
+                None => Err(format!("unknown command {} (try help)", command)),
+            },
+        };
+        Some(output)
+    }
+
+    /// Expands `!!` and `!N` into the history entry they name.
+    fn recall(&self, line: &str) -> Result<String, String> {
+        let entry = match line.strip_prefix('!') {
+            None => return Ok(line.to_string()),
+            Some("!") => self.history.last(),
+            Some(index) => index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| self.history.get(index)),
+        };
+        entry
+            .cloned()
+            .ok_or_else(|| format!("{}: no such command in history", line))
+    }
+}
+
+fn say(out: &mut impl Write, text: &str) -> Result<(), String> {
+    writeln!(out, "{}", text).map_err(|e| format!("output: {}", e))
+}
+
+/// Loads the day's input once and answers commands from stdin until `quit`
+/// or end of input.
+pub fn run(day: u8, input_path: &str) -> Result<(), String> {
+    run_with(day, input_path, io::stdin().lock(), &mut io::stdout())
+}
+
+/// `run`, reading commands from `commands` and writing prompts and replies
+/// to `out`.
+fn run_with(
+    day: u8,
+    input_path: &str,
+    commands: impl BufRead,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let input = read_input_bytes(input_path).map_err(|e| format!("{}: {}", input_path, e))?;
+    let mut session = Session {
+        day,
+        input_path: input_path.to_string(),
+        input,
+        history: Vec::new(),
+    };
+    say(
+        out,
+        &format!(
+            "day {}: loaded {} ({} bytes); type help for commands",
+            day,
+            input_path,
+            session.input.len()
+        ),
+    )?;
+
+    let mut lines = commands.lines();
+    loop {
+        write!(out, "day{:02}> ", day)
+            .and_then(|()| out.flush())
+            .map_err(|e| format!("output: {}", e))?;
+
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        let line = match session.recall(line.trim()) {
+            Ok(recalled) if recalled != line.trim() => {
+                say(out, &recalled)?;
+                recalled
+            }
+            Ok(line) => line,
+            Err(e) => {
+                say(out, &format!("error: {}", e))?;
+                continue;
+            }
+        };
+        if line.is_empty() {
+            continue;
+        }
+        session.history.push(line.clone());
+
+        match session.execute(&line) {
+            None => break,
+            Some(Ok(output)) if output.is_empty() => {}
+            Some(Ok(output)) => say(out, &output)?,
+            Some(Err(e)) => say(out, &format!("error: {}", e))?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY07: &str = "tests/fixtures/day07.txt";
+
+    fn session(day: u8, commands: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        run_with(day, DAY07, commands.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn answers_commands_until_quit() {
+        let out = session(
+            7,
+            "part1\nhand 1\n!!\nhand x\n!9\nfrobnicate\nhistory\nquit\npart2\n",
+        );
+        let hand = "32T3K bid 765: OnePair, rank 1 of 5, wins 765";
+
+        assert_eq!(
+            out[0],
+            format!("day 7: loaded {} (49 bytes); type help for commands", DAY07)
+        );
+        assert!(out[1].starts_with("day07> 6440 in "), "{}", out[1]);
+        assert_eq!(
+            out[2..],
+            [
+                format!("day07> part 1: {}", hand),
+                format!("part 2: {}", hand),
+                "day07> hand 1".to_string(),
+                format!("part 1: {}", hand),
+                format!("part 2: {}", hand),
+                "day07> error: invalid LINE: x (usage: hand LINE)".to_string(),
+                "day07> error: !9: no such command in history".to_string(),
+                "day07> error: unknown command frobnicate (try help)".to_string(),
+                "day07>    1  part1".to_string(),
+                "   2  hand 1".to_string(),
+                "   3  hand 1".to_string(),
+                "   4  hand x".to_string(),
+                "   5  frobnicate".to_string(),
+                "   6  history".to_string(),
+                "day07> ".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_commands() {
+        let out = session(7, "show 2 2");
+
+        assert_eq!(
+            out[1..],
+            ["day07>     2  T55J5 684", "    3  KK677 28", "day07> "]
+        );
+    }
+
+    #[test]
+    fn needs_the_input() {
+        let mut out = Vec::new();
+        let err = run_with(
+            7,
+            "tests/fixtures/missing.txt",
+            "quit\n".as_bytes(),
+            &mut out,
+        );
+
+        assert!(err.unwrap_err().starts_with("tests/fixtures/missing.txt: "));
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::Answer,
+    cache::Cache,
+    cancel,
+    crash,
+    error::{Error, Result},
+    history::{self, HistoryEntry},
+    memory,
+    read_input::{hash_file, hash_input, map_input, read_input_lines},
+    records::{now, Answers, LastRun, RecordPaths, VerifiedAnswer},
+    solutions::{self, Solution},
+    trace,
+    tsv::{Record, Table},
+};
+
+pub enum TraceOutput {
+    /// Indented events printed before each answer.
+    Text,
+    /// One JSON object per event, written to this file.
+    JsonLines(String),
+}
+
+#[derive(Default)]
+pub struct RunOptions {
+    /// Neither read nor write the answer cache.
+    pub no_cache: bool,
+    /// Ignore cached answers but store the freshly computed ones.
+    pub refresh: bool,
+    /// Record the answers as verified.
+    pub accept: bool,
+    /// Read the input line by line instead of loading it, skipping the cache.
+    pub stream: bool,
+    /// Report the peak heap usage of each solver.
+    pub memory: bool,
+    /// Record the solvers' trace events, bypassing the cache.
+    pub trace: Option<TraceOutput>,
+    /// Time budget for each part.
+    pub timeout: Option<Duration>,
+    /// Where the cache, answers, run records and crash reports are kept.
+    pub records: RecordPaths,
+}
+
+pub struct RunResult {
+    pub answer: Answer,
+    pub elapsed: Duration,
+    pub cached: bool,
+    /// Peak heap bytes allocated while solving, above what was live before.
+    pub peak_memory: usize,
+}
+
+pub fn run_solution(
+    solution: &Solution,
+    input: &[u8],
+    cache: Option<&mut Cache>,
+    options: &RunOptions,
+) -> Result<RunResult> {
+    let input_hash = hash_input(input);
+
+    if let Some(cache) = &cache {
+        if !options.refresh {
+            if let Some(answer) = cache.get(solution.day, solution.part, solution.version, input_hash) {
+                return Ok(RunResult {
+                    answer: answer.clone(),
+                    elapsed: Duration::ZERO,
+                    cached: true,
+                    peak_memory: 0,
+                });
+            }
+        }
+    }
+
+    let baseline = memory::reset_peak();
+    let start = Instant::now();
+    let answer = crash::catch(|| {
+        cancel::with_budget(options.timeout, || solution.solve_bytes(input))
+    })
+    .map_err(|panic| panicked(&options.records.crashes, solution, input_hash, panic))??;
+    let elapsed = start.elapsed();
+    let peak_memory = memory::peak().saturating_sub(baseline);
+
+    if let Some(cache) = cache {
+        cache.insert(solution.day, solution.part, solution.version, input_hash, answer.clone());
+    }
+
+    Ok(RunResult {
+        answer,
+        elapsed,
+        cached: false,
+        peak_memory,
+    })
+}
+
+/// Turns a caught solver panic into an error, writing its crash report to
+/// `crash_dir` on the way so the remaining parts can still run.
+pub fn panicked(crash_dir: &str, solution: &Solution, input_hash: u64, panic: crash::Panic) -> Error {
+    let report = crash::report(crash_dir, solution, input_hash, &panic);
+    if let Err(e) = &report {
+        eprintln!("{}: {}", crash_dir, e);
+    }
+
+    Error::Panicked {
+        message: panic.message,
+        location: panic.location,
+        report: report.ok(),
+    }
+}
+
+/// Runs a solution on lines read straight from `input_path`, so the input
+/// never has to fit in memory at once.
+pub fn run_streaming(
+    solution: &Solution,
+    input_path: &str,
+    options: &RunOptions,
+) -> Result<RunResult> {
+    let lines = read_input_lines(input_path)?;
+    let baseline = memory::reset_peak();
+    let start = Instant::now();
+    let answer = crash::catch(|| cancel::with_budget(options.timeout, || (solution.solve)(lines)))
+        .map_err(|panic| {
+            let input_hash = hash_file(input_path).unwrap_or(0);
+            panicked(&options.records.crashes, solution, input_hash, panic)
+        })??;
+    let elapsed = start.elapsed();
+
+    Ok(RunResult {
+        answer,
+        elapsed,
+        cached: false,
+        peak_memory: memory::peak().saturating_sub(baseline),
+    })
+}
+
+pub fn select(day: u8, part: Option<u8>) -> std::result::Result<Vec<&'static Solution>, String> {
+    let selected: Vec<&Solution> = solutions::for_day(day)
+        .filter(|s| part.is_none_or(|p| s.part == p))
+        .collect();
+    if selected.is_empty() {
+        return Err(format!("no solution registered for day {} part {:?}", day, part));
+    }
+
+    Ok(selected)
+}
+
+pub fn load_table<T: Record>(path: &str) -> std::result::Result<Table<T>, String> {
+    Table::load(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+pub fn save_table<T: Record>(table: &Table<T>, path: &str) -> std::result::Result<(), String> {
+    table.save().map_err(|e| format!("{}: {}", path, e))
+}
+
+pub fn run(
+    day: u8,
+    part: Option<u8>,
+    input_path: &str,
+    options: &RunOptions,
+) -> std::result::Result<(), String> {
+    run_solutions(day, &select(day, part)?, input_path, options)
+}
+
+/// Runs every solution in `selected`, carrying on past parts that fail and
+/// naming them in the error once the rest are done.
+fn run_solutions(
+    day: u8,
+    selected: &[&Solution],
+    input_path: &str,
+    options: &RunOptions,
+) -> std::result::Result<(), String> {
+    let input = if options.stream {
+        None
+    } else {
+        Some(map_input(input_path).map_err(|e| format!("{}: {}", input_path, e))?)
+    };
+    let mut trace_file = match &options.trace {
+        Some(TraceOutput::JsonLines(path)) => Some(BufWriter::new(
+            File::create(path).map_err(|e| format!("{}: {}", path, e))?,
+        )),
+        _ => None,
+    };
+    let records = &options.records;
+    let mut cache = if options.no_cache || options.stream || options.trace.is_some() {
+        None
+    } else {
+        Some(Cache::load(&records.cache).map_err(|e| format!("{}: {}", records.cache, e))?)
+    };
+    let mut answers: Answers = load_table(&records.answers)?;
+    let mut last_runs: Table<LastRun> = load_table(&records.last_run)?;
+    let input_hash = match &input {
+        Some(input) => hash_input(input),
+        None => hash_file(input_path).map_err(|e| format!("{}: {}", input_path, e))?,
+    };
+    let revision = history::git_revision();
+    let mut runs = Vec::new();
+    let mut failed = Vec::new();
+
+    for solution in selected {
+        let mut solve = || match &input {
+            Some(input) => run_solution(solution, input, cache.as_mut(), options),
+            None => run_streaming(solution, input_path, options),
+        };
+        let (outcome, events) = match options.trace {
+            Some(_) => trace::capture(solve),
+            None => (solve(), Vec::new()),
+        };
+        for event in &events {
+            match &mut trace_file {
+                Some(file) => {
+                    let mut json = event.to_json();
+                    json["day"] = day.into();
+                    json["part"] = solution.part.into();
+                    writeln!(file, "{}", json).map_err(|e| format!("trace: {}", e))?;
+                }
+                None => println!("{}", event.to_text()),
+            }
+        }
+
+        let result = match outcome {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Day {} part {}: error: {}", day, solution.part, e);
+                failed.push(format!("day {} part {}", day, solution.part));
+                continue;
+            }
+        };
+
+        if options.accept {
+            answers.insert(
+                day,
+                solution.part,
+                VerifiedAnswer {
+                    input_hash,
+                    answer: result.answer.clone(),
+                },
+            );
+        }
+        let expected = answers
+            .get(day, solution.part)
+            .and_then(|verified| verified.for_input(input_hash));
+        let verdict = match expected {
+            Some(expected) if *expected == result.answer => " (verified)".to_string(),
+            Some(expected) => format!(" (expected {})", expected),
+            None => String::new(),
+        };
+
+        if result.cached {
+            println!("Day {} part {}: {} (cached){}", day, solution.part, result.answer, verdict);
+        } else {
+            let memory = if options.memory {
+                format!(" using {}", memory::format_bytes(result.peak_memory))
+            } else {
+                String::new()
+            };
+            println!(
+                "Day {} part {}: {} in {:?}{}{}",
+                day, solution.part, result.answer, result.elapsed, memory, verdict
+            );
+            let timestamp = now();
+            runs.push(HistoryEntry {
+                timestamp,
+                revision: revision.clone(),
+                day,
+                part: solution.part,
+                answer: result.answer.clone(),
+                elapsed: result.elapsed,
+                input_hash,
+            });
+            last_runs.insert(
+                day,
+                solution.part,
+                LastRun {
+                    timestamp,
+                    input_hash,
+                    elapsed: result.elapsed,
+                    answer: result.answer,
+                },
+            );
+        }
+    }
+
+    history::append(&records.history, &runs)?;
+    if let Some(mut file) = trace_file {
+        file.flush().map_err(|e| format!("trace: {}", e))?;
+    }
+    if let Some(cache) = cache {
+        cache.save().map_err(|e| format!("{}: {}", records.cache, e))?;
+    }
+    if options.accept {
+        save_table(&answers, &records.answers)?;
+    }
+    save_table(&last_runs, &records.last_run)?;
+
+    if !failed.is_empty() {
+        return Err(format!("failed: {}", failed.join(", ")));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+    use crate::records::scratch_dir;
+
+    static SECOND_PART_RAN: AtomicBool = AtomicBool::new(false);
+
+    fn explode(_: crate::read_input::Input) -> Result<Answer> {
+        panic!("boom")
+    }
+
+    fn answer(_: crate::read_input::Input) -> Result<Answer> {
+        SECOND_PART_RAN.store(true, Ordering::SeqCst);
+        Ok(Answer::from(42_u64))
+    }
+
+    fn forty_two(_: crate::read_input::Input) -> Result<Answer> {
+        Ok(Answer::from(42_u64))
+    }
+
+    #[test]
+    fn no_cache_neither_reads_nor_writes_the_cache() {
+        let dir = scratch_dir("runner-no-cache");
+        let records = RecordPaths::under(&dir);
+        let solution = Solution { day: 97, part: 1, version: "1", solve: forty_two, solve_text: None };
+        let input_hash = hash_file("Cargo.toml").unwrap();
+        let mut cache = Cache::load(&records.cache).unwrap();
+        cache.insert(97, 1, "1", input_hash, Answer::from(7_u64));
+        cache.save().unwrap();
+        let run = |no_cache| {
+            let options = RunOptions {
+                no_cache,
+                records: records.clone(),
+                ..RunOptions::default()
+            };
+            run_solutions(97, &[&solution], "Cargo.toml", &options).unwrap();
+            load_table::<LastRun>(&records.last_run)
+                .unwrap()
+                .get(97, 1)
+                .map(|run| run.answer.clone())
+        };
+
+        // a cached answer isn't a run, so it leaves no record
+        assert_eq!(run(false), None);
+        assert_eq!(run(true), Some(Answer::from(42_u64)));
+        let cache = Cache::load(&records.cache).unwrap();
+        assert_eq!(
+            cache.get(97, 1, "1", input_hash),
+            Some(&Answer::from(7_u64))
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writes_the_trace_as_json_lines() {
+        let dir = scratch_dir("runner-trace");
+        let trace = dir.join("trace.jsonl").to_string_lossy().into_owned();
+        let options = RunOptions {
+            trace: Some(TraceOutput::JsonLines(trace.clone())),
+            records: RecordPaths::under(&dir),
+            ..RunOptions::default()
+        };
+
+        let selected = select(9, Some(1)).unwrap();
+        run_solutions(9, &selected, "tests/fixtures/day09.txt", &options).unwrap();
+        let events: Vec<serde_json::Value> = fs::read_to_string(&trace)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        fs::remove_dir_all(dir).unwrap();
+
+        let histories: Vec<&serde_json::Value> =
+            events.iter().filter(|e| e["event"] == "history").collect();
+        assert_eq!(histories.len(), 3);
+        assert_eq!(
+            events[0],
+            serde_json::json!({ "day": 9, "part": 1, "event": "history", "depth": 0, "line": 1 })
+        );
+        assert_eq!(events[1]["values"], serde_json::json!([0, 3, 6, 9, 12, 15]));
+        assert!(events.iter().all(|e| e["day"] == 9 && e["part"] == 1));
+    }
+
+    #[test]
+    fn reports_a_panic_and_runs_the_next_part() {
+        let dir = scratch_dir("runner-panic");
+        let day = 99;
+        let solutions = [
+            Solution { day, part: 1, version: "1", solve: explode, solve_text: None },
+            Solution { day, part: 2, version: "1", solve: answer, solve_text: None },
+        ];
+        let options = RunOptions {
+            no_cache: true,
+            records: RecordPaths::under(&dir),
+            ..RunOptions::default()
+        };
+
+        let result = run_solutions(day, &[&solutions[0], &solutions[1]], "Cargo.toml", &options);
+
+        assert_eq!(result, Err("failed: day 99 part 1".to_string()));
+        assert!(SECOND_PART_RAN.load(Ordering::SeqCst));
+
+        let reports: Vec<_> = fs::read_dir(&options.records.crashes)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(reports.len(), 1);
+        let report = fs::read_to_string(&reports[0]).unwrap();
+        assert!(reports[0].to_string_lossy().contains("day99-part1-"));
+        assert!(report.contains("message: boom"));
+
+        let runs = history::load(&options.records.history).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!((runs[0].part, &runs[0].answer), (2, &Answer::from(42_u64)));
+        assert!(load_table::<LastRun>(&options.records.last_run)
+            .unwrap()
+            .get(day, 2)
+            .is_some());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
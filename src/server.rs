@@ -0,0 +1,418 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    runner::{run_solution, RunOptions},
+    solutions,
+};
+
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+/// How long in all a connection turned away with a 503 gets to send its
+/// request; the accept loop waits for it meanwhile.
+const BUSY_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ServerOptions {
+    pub max_body_bytes: usize,
+    pub timeout: Duration,
+    /// Connections served at once, counting solvers still running after
+    /// their request timed out; further connections get a 503.
+    pub max_connections: usize,
+}
+
+/// One of `max_connections` slots, freed once the connection and its solver
+/// are both done with it.
+struct Permit(Arc<AtomicUsize>);
+
+impl Permit {
+    fn acquire(active: &Arc<AtomicUsize>, max: usize) -> Option<Permit> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Permit(Arc::clone(active)))
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn new(status: u16, body: Value) -> Response {
+        Response { status, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::new(status, json!({ "error": message.into() }))
+    }
+
+    fn write_to(&self, stream: &mut TcpStream) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "",
+        };
+        let body = self.body.to_string();
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+pub fn serve(addr: &str, options: ServerOptions) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Listening on http://{}", listener.local_addr()?);
+
+    listen(listener, options);
+    Ok(())
+}
+
+/// Serves connections from `listener` until accepting fails for good.
+pub fn listen(listener: TcpListener, options: ServerOptions) {
+    let active = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("accept error: {}", e);
+                continue;
+            }
+        };
+
+        match Permit::acquire(&active, options.max_connections) {
+            Some(permit) => {
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, options, Arc::new(permit)) {
+                        eprintln!("connection error: {}", e);
+                    }
+                });
+            }
+            None => {
+                // closing with the request unread would reset the connection
+                // before the client sees the response
+                let deadline = Deadline {
+                    stream: &stream,
+                    at: Instant::now() + BUSY_READ_TIMEOUT,
+                };
+                let _ = read_request(deadline, options.max_body_bytes);
+                let busy = Response::error(503, "too many requests in progress");
+                if let Err(e) = busy.write_to(&mut stream) {
+                    eprintln!("connection error: {}", e);
+                }
+            }
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    options: ServerOptions,
+    permit: Arc<Permit>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(options.timeout))?;
+
+    let response = match read_request(&stream, options.max_body_bytes) {
+        Ok(request) => route(request, options, permit),
+        Err(response) => response,
+    };
+
+    response.write_to(&mut stream)
+}
+
+/// Reads from a connection until a deadline for the whole request, however
+/// slowly the client trickles it in.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    at: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let left = self.at.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request too slow"));
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buffer)
+    }
+}
+
+fn read_request(stream: impl Read, max_body_bytes: usize) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut header_bytes = 0;
+    let mut lines = Vec::new();
+
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| Response::error(400, e.to_string()))?;
+        header_bytes += read;
+        if header_bytes > MAX_HEADER_BYTES {
+            return Err(Response::error(431, "request headers too large"));
+        }
+        if read == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    let request_line = lines
+        .first()
+        .ok_or_else(|| Response::error(400, "empty request"))?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(Response::error(400, "malformed request line")),
+    };
+
+    let mut content_length = 0;
+    for line in &lines[1..] {
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+            }
+        }
+    }
+
+    if content_length > max_body_bytes {
+        return Err(Response::error(
+            413,
+            format!("request body exceeds {} bytes", max_body_bytes),
+        ));
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| Response::error(400, e.to_string()))?;
+
+    Ok(Request { method, path, body })
+}
+
+fn route(request: Request, options: ServerOptions, permit: Arc<Permit>) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    match segments[..] {
+        ["solve", day, part] => {
+            if request.method != "POST" {
+                return Response::error(405, "use POST");
+            }
+            match (day.parse::<u8>(), part.parse::<u8>()) {
+                (Ok(day), Ok(part)) => solve(day, part, request.body, options.timeout, permit),
+                _ => Response::error(400, "day and part must be numbers"),
+            }
+        }
+        _ => Response::error(404, format!("no route for {}", request.path)),
+    }
+}
+
+fn solve(day: u8, part: u8, input: Vec<u8>, timeout: Duration, permit: Arc<Permit>) -> Response {
+    let solution = match solutions::find(day, part) {
+        Some(solution) => solution,
+        None => return Response::error(404, format!("no solution for day {} part {}", day, part)),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // the budget makes the solver stop once nobody waits for it any more;
+        // until then it keeps the connection's slot
+        let _permit = permit;
+        let options = RunOptions {
+            no_cache: true,
+            timeout: Some(timeout),
+            ..RunOptions::default()
+        };
+        let _ = sender.send(run_solution(solution, &input, None, &options));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(result)) => Response::new(
+            200,
+            json!({
+                "day": day,
+                "part": part,
+                "answer": result.answer.to_string(),
+                "elapsed_ms": result.elapsed.as_secs_f64() * 1000.0,
+            }),
+        ),
+        Ok(Err(e @ Error::Panicked { .. })) => Response::error(500, e.to_string()),
+        Ok(Err(e @ Error::TimedOut(_))) => Response::error(504, e.to_string()),
+        Ok(Err(e)) => Response::error(422, e.to_string()),
+        // run_solution catches panics, so the solver always sends eventually
+        Err(_) => Response::error(504, format!("timed out after {} s", timeout.as_secs_f64())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+
+    fn start(options: ServerOptions) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || listen(listener, options));
+        addr
+    }
+
+    fn options() -> ServerOptions {
+        ServerOptions {
+            max_body_bytes: 1024,
+            timeout: Duration::from_secs(5),
+            max_connections: 4,
+        }
+    }
+
+    /// Sends a raw request and returns the status and JSON body.
+    fn send(addr: SocketAddr, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn post(addr: SocketAddr, path: &str, body: &str) -> (u16, Value) {
+        send(
+            addr,
+            &format!(
+                "POST {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                path,
+                body.len(),
+                body
+            ),
+        )
+    }
+
+    /// Enough unknown springs that trying every arrangement takes minutes.
+    fn slow_springs() -> String {
+        format!("{} 1\n", "?".repeat(40))
+    }
+
+    #[test]
+    fn solves_a_part() {
+        let addr = start(options());
+        let (status, body) = post(addr, "/solve/6/1", "Time: 7 15 30\nDistance: 9 40 200\n");
+
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"], "288");
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let addr = start(options());
+
+        assert_eq!(post(addr, "/nowhere", "").0, 404);
+        assert_eq!(post(addr, "/solve/25/2", "").0, 404);
+        assert_eq!(send(addr, "GET /solve/6/1 HTTP/1.1\r\n\r\n").0, 405);
+        assert_eq!(post(addr, "/solve/6/1", &"1".repeat(2048)).0, 413);
+    }
+
+    #[test]
+    fn times_out_slow_solvers() {
+        let addr = start(ServerOptions {
+            timeout: Duration::from_millis(200),
+            ..options()
+        });
+        let (status, body) = post(addr, "/solve/12/1", &slow_springs());
+
+        assert_eq!(status, 504);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("timed out after"));
+    }
+
+    #[test]
+    fn turns_away_connections_beyond_the_limit() {
+        let addr = start(ServerOptions {
+            timeout: Duration::from_millis(500),
+            max_connections: 1,
+            ..options()
+        });
+        let slow = thread::spawn(move || post(addr, "/solve/12/1", &slow_springs()));
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(post(addr, "/solve/6/1", "Time: 7\nDistance: 9\n").0, 503);
+        assert_eq!(slow.join().unwrap().0, 504);
+    }
+
+    #[test]
+    fn a_trickling_client_cannot_stall_the_busy_replies() {
+        let addr = start(ServerOptions {
+            timeout: Duration::from_secs(3),
+            max_connections: 1,
+            ..options()
+        });
+        let slow = thread::spawn(move || post(addr, "/solve/12/1", &slow_springs()));
+        thread::sleep(Duration::from_millis(100));
+
+        // a byte every 50 ms never trips a per-read timeout
+        let trickle = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            for byte in b"POST /solve/6/1 HTTP/1.1\r\nContent-Length: 21\r\n\r\n".iter() {
+                if stream.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        assert_eq!(post(addr, "/solve/6/1", "Time: 7\nDistance: 9\n").0, 503);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        trickle.join().unwrap();
+        assert_eq!(slow.join().unwrap().0, 504);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    answer::Answer, error::Result, q03, q04, q05, q06, q07, q08, q09, q10, q11, q12,
+    read_input::{input_from_bytes, input_text, Input},
+};
+
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    /// Bump whenever the solver changes in a way that could change its answer,
+    /// so cached answers from the old code are not reused.
+    pub version: &'static str,
+    pub solve: fn(Input) -> Result<Answer>,
+    /// Same answer from the whole input as borrowed text, for days whose
+    /// parsers don't need owned lines; preferred when the input is in memory.
+    pub solve_text: Option<fn(&str) -> Result<Answer>>,
+}
+
+pub const SOLUTIONS: &[Solution] = &[
+    Solution { day: 3, part: 2, version: "1", solve: q03::part2, solve_text: None },
+    Solution { day: 4, part: 1, version: "2", solve: q04::part1, solve_text: None },
+    Solution { day: 4, part: 2, version: "2", solve: q04::part2, solve_text: None },
+    Solution { day: 5, part: 1, version: "2", solve: q05::part1, solve_text: None },
+    Solution { day: 5, part: 2, version: "2", solve: q05::part2, solve_text: None },
+    Solution { day: 6, part: 1, version: "2", solve: q06::part1, solve_text: None },
+    Solution { day: 6, part: 2, version: "2", solve: q06::part2, solve_text: None },
+    Solution { day: 7, part: 1, version: "1", solve: q07::part1, solve_text: Some(q07::part1_text) },
+    Solution { day: 7, part: 2, version: "1", solve: q07::part2, solve_text: Some(q07::part2_text) },
+    Solution { day: 8, part: 1, version: "1", solve: q08::part1, solve_text: Some(q08::part1_text) },
+    Solution { day: 8, part: 2, version: "1", solve: q08::part2, solve_text: Some(q08::part2_text) },
+    Solution { day: 9, part: 1, version: "1", solve: q09::part1, solve_text: None },
+    Solution { day: 9, part: 2, version: "1", solve: q09::part2, solve_text: None },
+    Solution { day: 10, part: 1, version: "1", solve: q10::part1, solve_text: Some(q10::part1_text) },
+    Solution { day: 10, part: 2, version: "1", solve: q10::part2, solve_text: Some(q10::part2_text) },
+    Solution { day: 11, part: 1, version: "1", solve: q11::part1, solve_text: None },
+    Solution { day: 11, part: 2, version: "1", solve: q11::part2, solve_text: None },
+    Solution { day: 12, part: 1, version: "1", solve: q12::part1, solve_text: None },
+];
+
+impl Solution {
+    /// Solves an input held in memory. Days without `solve_text` copy it into
+    /// owned lines first, so mapping the file gains them nothing.
+    pub fn solve_bytes(&self, input: &[u8]) -> Result<Answer> {
+        match self.solve_text {
+            Some(solve_text) => solve_text(input_text(input)?),
+            None => (self.solve)(input_from_bytes(input.to_vec())),
+        }
+    }
+}
+
+pub fn find(day: u8, part: u8) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|s| s.day == day && s.part == part)
+}
+
+pub fn for_day(day: u8) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(move |s| s.day == day)
+}
+
+pub fn default_input_path(day: u8) -> String {
+    format!("input{:02}.txt", day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_some_days_borrow_their_input() {
+        let borrowing: Vec<_> = SOLUTIONS
+            .iter()
+            .filter(|s| s.solve_text.is_some())
+            .map(|s| (s.day, s.part))
+            .collect();
+
+        assert_eq!(
+            borrowing,
+            [(7, 1), (7, 2), (8, 1), (8, 2), (10, 1), (10, 2)]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::{
+    records::{
+        now, Answers, BenchResult, LastRun, VerifiedAnswer, ANSWERS_PATH, BENCH_PATH, LAST_RUN_PATH,
+    },
+    runner::load_table,
+    solutions,
+    tsv::Table,
+};
+
+#[derive(Debug)]
+pub struct PartStatus {
+    pub part: u8,
+    pub implemented: bool,
+    pub verified_answer: Option<VerifiedAnswer>,
+    pub last_run: Option<LastRun>,
+    pub bench: Option<BenchResult>,
+}
+
+impl PartStatus {
+    /// Whether the last run matched the verified answer, if both are known
+    /// and for the same input.
+    pub fn correct(&self) -> Option<bool> {
+        let last_run = self.last_run.as_ref()?;
+        let expected = self
+            .verified_answer
+            .as_ref()?
+            .for_input(last_run.input_hash)?;
+        Some(last_run.answer == *expected)
+    }
+
+    fn marker(&self) -> char {
+        match (self.implemented, self.correct(), &self.last_run) {
+            (false, _, _) => ' ',
+            (true, Some(true), _) => '*',
+            (true, Some(false), _) => '!',
+            (true, None, Some(_)) => '+',
+            (true, None, None) => '.',
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.marker() {
+            '*' => "verified",
+            '!' => "wrong",
+            '+' => "unverified",
+            '.' => "not run",
+            _ => "missing",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DayStatus {
+    pub day: u8,
+    pub parts: Vec<PartStatus>,
+}
+
+pub fn collect() -> Result<Vec<DayStatus>, String> {
+    let answers: Answers = load_table(ANSWERS_PATH)?;
+    let last_runs: Table<LastRun> = load_table(LAST_RUN_PATH)?;
+    let benches: Table<BenchResult> = load_table(BENCH_PATH)?;
+
+    Ok((1..=25)
+        .map(|day| DayStatus {
+            day,
+            parts: (1..=2)
+                .map(|part| PartStatus {
+                    part,
+                    implemented: solutions::find(day, part).is_some(),
+                    verified_answer: answers.get(day, part).cloned(),
+                    last_run: last_runs.get(day, part).cloned(),
+                    bench: benches.get(day, part).cloned(),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{}s ago", seconds),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn print_calendar(days: &[DayStatus]) {
+    for week in days.chunks(5) {
+        let cells: Vec<String> = week
+            .iter()
+            .map(|d| {
+                let markers: String = d.parts.iter().map(PartStatus::marker).collect();
+                format!("[{:>2} {}]", d.day, markers)
+            })
+            .collect();
+        println!("{}", cells.join(" "));
+    }
+    println!("* verified  ! wrong  + unverified  . not run");
+    println!();
+
+    println!(
+        "{:>3} {:>4}  {:<10} {:>9} {:>12} {:>12}",
+        "Day", "Part", "Status", "Last run", "Elapsed", "Bench"
+    );
+    let now = now();
+    for day in days {
+        for part in day.parts.iter().filter(|p| p.implemented) {
+            println!(
+                "{:>3} {:>4}  {:<10} {:>9} {:>12} {:>12}",
+                day.day,
+                part.part,
+                part.label(),
+                part.last_run
+                    .as_ref()
+                    .map(|r| format_age(now.saturating_sub(r.timestamp)))
+                    .unwrap_or_else(|| "-".to_string()),
+                part.last_run
+                    .as_ref()
+                    .map(|r| format!("{:.2?}", r.elapsed))
+                    .unwrap_or_else(|| "-".to_string()),
+                part.bench
+                    .as_ref()
+                    .map(|b| format!("{:.2?}", b.median))
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+        }
+    }
+}
+
+pub fn to_json(days: &[DayStatus]) -> Value {
+    Value::Array(
+        days.iter()
+            .map(|d| {
+                json!({
+                    "day": d.day,
+                    "parts": d.parts.iter().map(|p| json!({
+                        "part": p.part,
+                        "implemented": p.implemented,
+                        "status": p.label(),
+                        "verified_answer": p.verified_answer.as_ref().map(|v| v.answer.to_string()),
+                        "last_answer": p.last_run.as_ref().map(|r| r.answer.to_string()),
+                        "last_run": p.last_run.as_ref().map(|r| r.timestamp),
+                        "last_elapsed_ms": p.last_run.as_ref().map(|r| millis(r.elapsed)),
+                        "bench_median_ms": p.bench.as_ref().map(|b| millis(b.median)),
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(verified: Option<(u64, u64)>, last_run: Option<(u64, u64)>) -> PartStatus {
+        PartStatus {
+            part: 1,
+            implemented: true,
+            verified_answer: verified.map(|(input_hash, answer)| VerifiedAnswer {
+                input_hash,
+                answer: answer.into(),
+            }),
+            last_run: last_run.map(|(input_hash, answer)| LastRun {
+                timestamp: 0,
+                input_hash,
+                elapsed: Duration::ZERO,
+                answer: answer.into(),
+            }),
+            bench: None,
+        }
+    }
+
+    #[test]
+    fn compares_runs_on_the_accepted_input_only() {
+        assert_eq!(part(Some((1, 42)), Some((1, 42))).label(), "verified");
+        assert_eq!(part(Some((1, 42)), Some((1, 41))).label(), "wrong");
+        assert_eq!(part(Some((1, 42)), Some((2, 41))).label(), "unverified");
+        assert_eq!(part(None, Some((2, 41))).label(), "unverified");
+        assert_eq!(part(Some((1, 42)), None).label(), "not run");
+    }
+}
@@ -0,0 +1,228 @@
+use std::cell::RefCell;
+
+use serde_json::Map;
+pub use serde_json::Value;
+
+/// One step a solver reported; `depth` counts the spans around it.
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub depth: usize,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Event {
+    /// `name key=value ...`, indented two spaces per depth.
+    pub fn to_text(&self) -> String {
+        let mut line = format!("{:indent$}{}", "", self.name, indent = self.depth * 2);
+        for (key, value) in &self.fields {
+            match value {
+                Value::String(text) => line.push_str(&format!(" {}={}", key, text)),
+                _ => line.push_str(&format!(" {}={}", key, value)),
+            }
+        }
+        line
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("event".to_string(), Value::from(self.name));
+        object.insert("depth".to_string(), Value::from(self.depth));
+        for (key, value) in &self.fields {
+            object.insert(key.to_string(), value.clone());
+        }
+        Value::Object(object)
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    depth: usize,
+    events: Vec<Event>,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Whether events on this thread are being recorded; the `trace!` macros
+/// check this before building any fields.
+pub fn enabled() -> bool {
+    RECORDER.with(|recorder| recorder.borrow().is_some())
+}
+
+pub fn emit(name: &'static str, fields: Vec<(&'static str, Value)>) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            recorder.events.push(Event {
+                depth: recorder.depth,
+                name,
+                fields,
+            });
+        }
+    });
+}
+
+/// Nests the events emitted while it is alive one level deeper.
+pub struct Span {
+    active: bool,
+}
+
+impl Span {
+    pub fn disabled() -> Span {
+        Span { active: false }
+    }
+}
+
+pub fn enter(name: &'static str, fields: Vec<(&'static str, Value)>) -> Span {
+    emit(name, fields);
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            recorder.depth += 1;
+        }
+    });
+    Span { active: true }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.active {
+            RECORDER.with(|recorder| {
+                if let Some(recorder) = recorder.borrow_mut().as_mut() {
+                    recorder.depth = recorder.depth.saturating_sub(1);
+                }
+            });
+        }
+    }
+}
+
+/// Turns recording off again even if `capture`'s closure panics.
+struct Stop;
+
+impl Drop for Stop {
+    fn drop(&mut self) {
+        RECORDER.with(|recorder| recorder.borrow_mut().take());
+    }
+}
+
+/// Runs `f` with tracing enabled on this thread and returns what it emitted.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<Event>) {
+    RECORDER.with(|recorder| *recorder.borrow_mut() = Some(Recorder::default()));
+    let stop = Stop;
+    let result = f();
+    let events = RECORDER
+        .with(|recorder| recorder.borrow_mut().take())
+        .map(|recorder| recorder.events)
+        .unwrap_or_default();
+    drop(stop);
+
+    (result, events)
+}
+
+/// Records an event when tracing is enabled, e.g.
+/// `trace!("hop", from = seed, to = next)`. Values go through `Value::from`.
+#[macro_export]
+macro_rules! trace {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled() {
+            $crate::trace::emit(
+                $name,
+                vec![$((stringify!($key), $crate::trace::Value::from($value))),*],
+            );
+        }
+    };
+}
+
+/// Like `trace!`, but returns a guard that indents the events recorded
+/// until it is dropped.
+#[macro_export]
+macro_rules! trace_span {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled() {
+            $crate::trace::enter(
+                $name,
+                vec![$((stringify!($key), $crate::trace::Value::from($value))),*],
+            )
+        } else {
+            $crate::trace::Span::disabled()
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{q09, read_input::input_from_bytes};
+
+    fn first_history() -> Vec<Event> {
+        let input = input_from_bytes(b"0 3 6 9 12 15\n".to_vec());
+        let (answer, events) = capture(|| q09::part1(input));
+        assert_eq!(answer.unwrap().to_string(), "18");
+        events
+    }
+
+    #[test]
+    fn nests_events_inside_spans() {
+        let (_, events) = capture(|| {
+            crate::trace!("before");
+            {
+                let _outer = crate::trace_span!("outer", n = 1);
+                let _inner = crate::trace_span!("inner");
+                crate::trace!("deepest", text = "x");
+            }
+            crate::trace!("after");
+        });
+        let depths: Vec<(&str, usize)> = events.iter().map(|e| (e.name, e.depth)).collect();
+
+        assert_eq!(
+            depths,
+            [
+                ("before", 0),
+                ("outer", 0),
+                ("inner", 1),
+                ("deepest", 2),
+                ("after", 0)
+            ]
+        );
+        assert!(!enabled());
+        crate::trace!("ignored");
+        assert!(capture(|| ()).1.is_empty());
+    }
+
+    #[test]
+    fn prints_a_solvers_spans_as_text() {
+        let lines: Vec<String> = first_history().iter().map(Event::to_text).collect();
+
+        assert_eq!(
+            lines,
+            [
+                "history line=1",
+                "  row values=[0,3,6,9,12,15]",
+                "    row values=[3,3,3,3,3]",
+                "      row values=[0,0,0,0]",
+                "  extrapolated value=18",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_a_solvers_spans_as_json() {
+        let objects: Vec<Value> = first_history().iter().map(Event::to_json).collect();
+
+        assert_eq!(objects.len(), 5);
+        assert_eq!(
+            objects[0],
+            json!({ "event": "history", "depth": 0, "line": 1 })
+        );
+        assert_eq!(
+            objects[2],
+            json!({ "event": "row", "depth": 2, "values": [3, 3, 3, 3, 3] })
+        );
+        assert_eq!(
+            objects[4],
+            json!({ "event": "extrapolated", "depth": 1, "value": 18 })
+        );
+    }
+}
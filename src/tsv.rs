@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+/// Reads a tab separated file, treating a missing file as empty.
+pub fn read_rows(path: &Path) -> io::Result<Vec<Vec<String>>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn render_rows(rows: impl IntoIterator<Item = Vec<String>>) -> String {
+    let mut contents = String::new();
+    for row in rows {
+        contents.push_str(&row.join("\t"));
+        contents.push('\n');
+    }
+    contents
+}
+
+pub fn write_rows(path: &Path, rows: impl IntoIterator<Item = Vec<String>>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, render_rows(rows))
+}
+
+/// Adds rows to the end of the file, creating it if needed.
+pub fn append_rows(path: &Path, rows: impl IntoIterator<Item = Vec<String>>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(render_rows(rows).as_bytes())
+}
+
+pub trait Record: Sized {
+    fn from_fields(fields: &[String]) -> Option<Self>;
+    fn to_fields(&self) -> Vec<String>;
+}
+
+impl Record for String {
+    fn from_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [value] => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_fields(&self) -> Vec<String> {
+        vec![self.clone()]
+    }
+}
+
+/// One record per (day, part), stored as `day<TAB>part<TAB>fields...` lines.
+#[derive(Debug)]
+pub struct Table<T> {
+    path: PathBuf,
+    rows: BTreeMap<(u8, u8), T>,
+}
+
+impl<T: Record> Table<T> {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Table<T>> {
+        let path = path.as_ref().to_path_buf();
+        let mut rows = BTreeMap::new();
+        for row in read_rows(&path)? {
+            if let [day, part, fields @ ..] = &row[..] {
+                if let (Ok(day), Ok(part), Some(record)) =
+                    (day.parse(), part.parse(), T::from_fields(fields))
+                {
+                    rows.insert((day, part), record);
+                }
+            }
+        }
+
+        Ok(Table { path, rows })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&T> {
+        self.rows.get(&(day, part))
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, record: T) {
+        self.rows.insert((day, part), record);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&(u8, u8), &T)> {
+        self.rows.iter()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_rows(
+            &self.path,
+            self.rows.iter().map(|((day, part), record)| {
+                let mut row = vec![day.to_string(), part.to_string()];
+                row.extend(record.to_fields());
+                row
+            }),
+        )
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::{
+    error::{Error, Result},
+    q04, q07, q08, q10, q11,
+    read_input::Input,
+};
+
+/// A broken puzzle invariant; `line` is 1-based, `None` for the input as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn at(line: usize, message: impl Into<String>) -> Violation {
+        Violation {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+
+    pub fn global(message: impl Into<String>) -> Violation {
+        Violation {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    /// The violation an error parsing the input stands for, on its line.
+    pub fn from_error(error: Error) -> Violation {
+        match error {
+            Error::Parse { line, kind } => Violation::at(line, kind.to_string()),
+            error => Violation::global(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+pub type Validator = fn(Input) -> Result<Vec<Violation>>;
+
+pub fn validator(day: u8) -> Option<Validator> {
+    match day {
+        4 => Some(q04::validate),
+        7 => Some(q07::validate),
+        8 => Some(q08::validate),
+        10 => Some(q10::validate),
+        11 => Some(q11::validate),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_input::input_from_bytes;
+
+    fn violations(day: u8, input: &str) -> Vec<String> {
+        let validator = validator(day).unwrap();
+        validator(input_from_bytes(input.as_bytes().to_vec()))
+            .unwrap()
+            .iter()
+            .map(Violation::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn day04() {
+        assert!(violations(4, include_str!("../tests/fixtures/day04.txt")).is_empty());
+        assert_eq!(
+            violations(4, "Card 1: 1 2 3 | 1 4\nCard 2: 1 2 3\nCard 3: 1 2 | 4\n"),
+            [
+                "line 2: missing separator '|'",
+                "line 3: expected 3 winning numbers, found 2",
+            ]
+        );
+        assert_eq!(
+            violations(4, "Card 1: 1 2 | 1 2\nCard 2: 3 4 | 5 6\n"),
+            ["line 1: card wins 1 copies past the end of the table"]
+        );
+    }
+
+    #[test]
+    fn day07() {
+        assert!(violations(7, include_str!("../tests/fixtures/day07.txt")).is_empty());
+        assert_eq!(
+            violations(7, "A 1\n32T3X 765\n32T3K x\n32T3K\n"),
+            [
+                "line 1: expected 5 cards, found 1",
+                "line 2: invalid card X",
+                "line 3: invalid bid x",
+                "line 4: missing separator ' '",
+            ]
+        );
+    }
+
+    #[test]
+    fn day08() {
+        assert!(violations(8, include_str!("../tests/fixtures/day08.txt")).is_empty());
+        assert_eq!(
+            violations(
+                8,
+                "L\n\nAAA = (BBB, QQQ)\nBBB = (ZZZ, ZZZ)\nZZZ = (BBB, BBB)\n"
+            ),
+            ["line 3: AAA refers to undefined node QQQ"]
+        );
+    }
+
+    #[test]
+    fn day08_checks_the_ghosts_without_aaa() {
+        assert_eq!(
+            violations(
+                8,
+                "L\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (11Z, 11Z)\n"
+            ),
+            [
+                "part 1 needs both AAA and ZZZ",
+                "line 3: ghost from 11A reaches 11Z after 2 steps but 11Z after 1 more, \
+                 so LCM does not apply",
+            ]
+        );
+    }
+
+    #[test]
+    fn day10() {
+        assert!(violations(10, include_str!("../tests/fixtures/day10.txt")).is_empty());
+        assert_eq!(violations(10, ".|.\n-L-\n"), ["missing start tile S"]);
+        assert_eq!(
+            violations(10, "S-7\n|.|\nL-x\n"),
+            ["line 3: invalid tile x in column 3"]
+        );
+    }
+
+    #[test]
+    fn day11() {
+        assert!(violations(11, include_str!("../tests/fixtures/day11.txt")).is_empty());
+        assert_eq!(
+            violations(11, "#.#\n..\n.x.\n"),
+            [
+                "line 2: row is 2 wide, expected 3",
+                "line 3: invalid cell x"
+            ]
+        );
+    }
+}
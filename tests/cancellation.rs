@@ -0,0 +1,88 @@
+//! Inputs big enough to pass many cancellation points, checking that solvers
+//! other than the brute force ones stop once their budget is spent.
+
+use std::time::Duration;
+
+use aoc::{
+    answer::Answer,
+    cancel,
+    error::{Error, Result},
+    q05, q10,
+    read_input::{input_from_bytes, Input},
+};
+
+fn input(text: &str) -> Input {
+    input_from_bytes(text.as_bytes().to_vec())
+}
+
+fn assert_timed_out<T: std::fmt::Debug>(solve: impl FnOnce() -> Result<T>) {
+    match cancel::with_budget(Some(Duration::ZERO), solve) {
+        Err(e @ Error::TimedOut(_)) => assert_eq!(e.to_string(), "timed out after 0 s"),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+/// One map with `mappings` mappings, each moving five seeds up by a million.
+fn almanac(seeds: &str, mappings: usize) -> String {
+    let mut text = format!("seeds: {}\n\nseed-to-soil map:\n", seeds);
+    for i in 0..mappings {
+        text.push_str(&format!("{} {} 5\n", 1_000_000 + i * 10, i * 10));
+    }
+    text
+}
+
+/// A square loop of pipes around the edge of a `size` by `size` grid.
+fn square_loop(size: usize) -> String {
+    let inner = size - 2;
+    let mut text = format!("S{}7\n", "-".repeat(inner));
+    for _ in 0..inner {
+        text.push_str(&format!("|{}|\n", ".".repeat(inner)));
+    }
+    text.push_str(&format!("L{}J\n", "-".repeat(inner)));
+    text
+}
+
+#[test]
+fn q05_stops_mapping_seeds() {
+    let seeds = (0..2000)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = almanac(&seeds, 1);
+
+    assert_eq!(q05::part1(input(&text)).unwrap(), Answer::from(5_u64));
+    assert_timed_out(|| q05::part1(input(&text)));
+}
+
+#[test]
+fn q05_stops_mapping_ranges() {
+    let text = almanac("0 100000", 2000);
+
+    assert_eq!(q05::part2(input(&text)).unwrap(), Answer::from(5_u64));
+    assert_timed_out(|| q05::part2(input(&text)));
+}
+
+#[test]
+fn q10_stops_following_the_loop() {
+    let text = square_loop(400);
+
+    assert_eq!(q10::part1(input(&text)).unwrap(), Answer::from(798_u64));
+    assert_timed_out(|| q10::part1(input(&text)));
+}
+
+#[test]
+fn q10_stops_counting_enclosed_tiles() {
+    // the loop is too short to time out, the tiles inside it are not
+    let text = square_loop(100);
+
+    assert_eq!(q10::part2(input(&text)).unwrap(), Answer::from(98_u64 * 98));
+    assert_timed_out(|| q10::part2(input(&text)));
+}
+
+#[test]
+fn an_endless_budget_is_no_limit() {
+    let text = square_loop(400);
+
+    let solved = cancel::with_budget(Some(Duration::MAX), || q10::part1(input(&text)));
+    assert_eq!(solved.unwrap(), Answer::from(798_u64));
+}
@@ -0,0 +1,31 @@
+//! Inputs that once made a solver panic; each must now be rejected with a
+//! parse error on the offending line.
+
+use aoc::{
+    answer::Answer,
+    error::{Error, Result},
+    q07,
+    read_input::input_from_bytes,
+};
+
+fn assert_rejected(result: Result<Answer>, at_line: usize) {
+    match result {
+        Err(Error::Parse { line, .. }) => assert_eq!(line, at_line),
+        other => panic!(
+            "expected a parse error on line {}, got {:?}",
+            at_line, other
+        ),
+    }
+}
+
+#[test]
+fn q07_hands_of_other_lengths() {
+    // comparing these two hands indexed past the end of the shorter one
+    let text = "A 1\nAK 2\n";
+
+    assert_rejected(q07::part1_text(text), 1);
+    assert_rejected(q07::part2_text(text), 1);
+    assert_rejected(q07::part1(input_from_bytes(text.into())), 1);
+    assert_rejected(q07::part2(input_from_bytes(text.into())), 1);
+    assert_rejected(q07::part1_text("AKQJT9 1\n"), 1);
+}
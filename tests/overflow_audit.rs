@@ -0,0 +1,182 @@
+//! Synthetic inputs far beyond real puzzle sizes, checking that every day
+//! reports an overflow with its day and operation, or rejects the input,
+//! instead of a wrong answer.
+
+use aoc::{
+    answer::Answer,
+    checked,
+    error::Error,
+    q03, q04, q05, q06, q07, q08, q09, q10, q11, q12,
+    read_input::{input_from_bytes, Input},
+};
+
+fn input(text: String) -> Input {
+    input_from_bytes(text.into_bytes())
+}
+
+fn assert_overflow<T: std::fmt::Debug>(result: aoc::error::Result<T>, operation: &str) {
+    match result {
+        Err(Error::Overflow(found)) => assert_eq!(found, operation),
+        other => panic!("expected overflow in {}, got {:?}", operation, other),
+    }
+}
+
+fn assert_rejected<T: std::fmt::Debug>(result: aoc::error::Result<T>, at_line: usize) {
+    match result {
+        Err(Error::Parse { line, .. }) => assert_eq!(line, at_line),
+        other => panic!(
+            "expected a parse error on line {}, got {:?}",
+            at_line, other
+        ),
+    }
+}
+
+#[test]
+fn q03_gear_ratio() {
+    let text = "9999999999*9999999999\n".to_string();
+    assert_overflow(q03::part2(input(text)), "q03 gear ratio");
+}
+
+#[test]
+fn q04_card_copies() {
+    // every card wins copies of the next ten, so the copies grow like a
+    // ten-step Fibonacci sequence; the last ten cards win nothing
+    let winning = (1..=10)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut text = String::new();
+    for card in 1..=200 {
+        let guesses = if card <= 190 {
+            winning.as_str()
+        } else {
+            "91 92 93"
+        };
+        text.push_str(&format!("Card {}: {} | {}\n", card, winning, guesses));
+    }
+
+    assert_overflow(q04::part2(input(text)), "q04 card count");
+}
+
+#[test]
+fn q05_mapping_range() {
+    // a mapping ending exactly at usize::MAX still maps, one past it is rejected
+    let top = usize::MAX - 5;
+    let text = format!("seeds: 1\n\nseed-to-soil map:\n{} 0 5\n", top);
+    assert_eq!(
+        q05::part1(input(text)).unwrap(),
+        Answer::from(top as u64 + 1)
+    );
+
+    let text = format!("seeds: 1\n\nseed-to-soil map:\n{} 0 6\n", top);
+    assert_rejected(q05::part1(input(text)), 4);
+}
+
+#[test]
+fn q06_product() {
+    let text = "Time: 4000000000 4000000000 4000000000\nDistance: 1 1 1\n".to_string();
+    assert_overflow(q06::part1(input(text)), "q06 product");
+}
+
+#[test]
+fn q07_winnings() {
+    let cards = ['A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
+    let mut text = String::new();
+    for i in 0..120_000_usize {
+        let hand: String = (0..5).map(|k| cards[i / 12_usize.pow(k) % 12]).collect();
+        text.push_str(&format!("{} {}\n", hand, u32::MAX));
+    }
+
+    assert_overflow(q07::part1(input(text)), "q07 winnings");
+}
+
+#[test]
+fn q08_lcm() {
+    // one ghost per prime, each reaching its ..Z node after that many steps;
+    // the product of the first 28 primes doesn't fit in a u128
+    let primes: Vec<usize> = (2..)
+        .filter(|n: &usize| {
+            (2..*n)
+                .take_while(|d| d * d <= *n)
+                .all(|d| !n.is_multiple_of(d))
+        })
+        .take(28)
+        .collect();
+    let mut text = "L\n\n".to_string();
+    for (ghost, prime) in primes.iter().enumerate() {
+        let node = |step: usize| match step {
+            0 => format!("G{}A", ghost),
+            s if s == *prime => format!("G{}Z", ghost),
+            s => format!("G{}N{}", ghost, s),
+        };
+        for step in 0..*prime {
+            text.push_str(&format!(
+                "{} = ({}, {})\n",
+                node(step),
+                node(step + 1),
+                node(step + 1)
+            ));
+        }
+        text.push_str(&format!(
+            "{} = ({}, {})\n",
+            node(*prime),
+            node(*prime),
+            node(*prime)
+        ));
+    }
+
+    assert_overflow(q08::part2(input(text)), "q08 lcm");
+}
+
+#[test]
+fn q09_difference() {
+    let text = format!("{} {} {}\n", i32::MAX, i32::MIN, i32::MAX);
+    assert_overflow(q09::part1(input(text)), "q09 difference");
+}
+
+#[test]
+fn q10_grid_edges() {
+    // following pipes off the top or left edge must not wrap around
+    assert_eq!(
+        q10::part1(input("S7\nLJ\n".to_string())).unwrap(),
+        Answer::from(2_u64)
+    );
+    match q10::part1(input("|S\n".to_string())) {
+        Err(Error::NoSolution(_)) => {}
+        other => panic!("expected no solution, got {:?}", other),
+    }
+}
+
+#[test]
+fn q11_distance() {
+    // two blocks of full rows far apart: every pair across them is more than
+    // a hundred million rows apart once the empty rows are expanded
+    let full = format!("{}\n", "#".repeat(1000));
+    let empty = format!("{}\n", ".".repeat(1000));
+    let text = full.repeat(500) + &empty.repeat(100) + &full.repeat(500);
+
+    assert_overflow(q11::part2(input(text)), "q11 distance");
+}
+
+#[test]
+fn q12_group_length() {
+    let text = format!("???.### 1,1,{}0\n", usize::MAX);
+    assert_rejected(q12::part1(input(text)), 1);
+}
+
+#[cfg(feature = "checked-arithmetic")]
+#[test]
+fn counters_are_checked() {
+    assert_overflow(checked::add(u8::MAX, 1, "add"), "add");
+    assert_overflow(checked::sub(0_usize, 1, "sub"), "sub");
+    assert_overflow(checked::mul(u64::MAX, 2, "mul"), "mul");
+    assert_overflow(checked::cast::<i128, usize>(-1, "cast"), "cast");
+    assert_eq!(checked::cast::<i128, usize>(7, "cast").unwrap(), 7);
+}
+
+#[cfg(not(feature = "checked-arithmetic"))]
+#[test]
+fn counters_are_plain() {
+    assert_eq!(checked::add(2_u8, 3, "add").unwrap(), 5);
+    assert_eq!(checked::cast::<i128, u8>(300, "cast").unwrap(), 44);
+}
@@ -0,0 +1,73 @@
+//! `run --stream` reads the input line by line instead of loading it; both
+//! ways must give the same answers, and `--memory` must report what the
+//! solvers allocated.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+use aoc::{
+    memory::CountingAlloc,
+    runner::{run_solution, run_streaming, RunOptions},
+    solutions::SOLUTIONS,
+};
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+fn example(day: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/day{:02}.txt", day))
+}
+
+#[test]
+fn streamed_and_loaded_inputs_give_the_same_answers() {
+    let options = RunOptions::default();
+
+    for solution in SOLUTIONS {
+        let path = example(solution.day);
+        let input = fs::read(&path).unwrap();
+        let loaded = run_solution(solution, &input, None, &options).unwrap();
+        let streamed = run_streaming(solution, &path.to_string_lossy(), &options).unwrap();
+
+        let name = format!("day {} part {}", solution.day, solution.part);
+        assert_eq!(loaded.answer, streamed.answer, "{}", name);
+        assert!(loaded.peak_memory > 0, "{}", name);
+        assert!(streamed.peak_memory > 0, "{}", name);
+    }
+}
+
+#[test]
+fn run_reports_the_memory_used() {
+    // a directory of its own, so the run's records stay out of the real ones
+    let dir = env::temp_dir().join(format!("aoc-stream-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let run = |extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+            .args(["run", "--day", "6", "--no-cache", "--memory", "--input"])
+            .arg(example(6))
+            .args(extra)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let loaded = run(&[]);
+    let streamed = run(&["--stream"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    for output in [&loaded, &streamed] {
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", output);
+        assert!(lines[0].starts_with("Day 6 part 1: 288 in "), "{}", output);
+        assert!(
+            lines[1].starts_with("Day 6 part 2: 71503 in "),
+            "{}",
+            output
+        );
+        assert!(
+            lines.iter().all(|line| line.contains(" using ")),
+            "{}",
+            output
+        );
+    }
+}